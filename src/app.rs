@@ -1,5 +1,44 @@
+use std::{cell::RefCell, rc::Rc};
+
 use egui_plot::{Line, PlotPoints};
 
+use crate::experiment::{self, Experiment, Setup, Spread, Trial};
+use crate::figure::{self, Figure};
+use crate::metrics::{self, WaitStats};
+use crate::quiz::{Grade, Problem};
+use crate::scenario::Scenario;
+use crate::sched::{
+    format_requests, parse_cylinders, parse_requests, Algorithm, DeadlineTunables, Direction,
+    DiskScheduler, Geometry, Op, Platter, Request, Schedule, StepKind, TieBreak, Tuning,
+    MAX_CYLINDERS, MAX_REQUESTS,
+};
+#[cfg(target_arch = "wasm32")]
+use crate::share::Link;
+use crate::sim::{parse_seek_table, time_schedule, SeekModel, Simulation, TimedStep};
+use crate::table::{self, Table, TableFormat};
+use crate::trace::{
+    self, parse_blkparse, parse_csv, CsvImport, SectorMapping, TimeUnit, TraceEvent,
+};
+use crate::workload::{self, Distribution, WorkloadSpec};
+
+/// One colour per entry of [`Algorithm::ALL`], in the same order.
+const ALGORITHM_COLORS: [egui::Color32; Algorithm::ALL.len()] = [
+    egui::Color32::from_rgb(31, 119, 180),
+    egui::Color32::from_rgb(255, 127, 14),
+    egui::Color32::from_rgb(44, 160, 44),
+    egui::Color32::from_rgb(214, 39, 40),
+    egui::Color32::from_rgb(148, 103, 189),
+    egui::Color32::from_rgb(140, 86, 75),
+    egui::Color32::from_rgb(227, 119, 194),
+    egui::Color32::from_rgb(188, 189, 34),
+    egui::Color32::from_rgb(23, 190, 207),
+    egui::Color32::from_rgb(127, 127, 127),
+];
+
+/// Requests an experiment may generate and simulate in one frame, so a long
+/// run keeps the window responsive.
+const EXPERIMENT_REQUESTS_PER_FRAME: usize = 500;
+
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)] // if we add new fields, give them default values when deserializing old state
@@ -7,23 +46,260 @@ pub struct TemplateApp {
     cylinder_count: u32,
     arm_position_int: u32,
     sequence: Vec<u32>,
+    /// Arrival time in ms of each entry of `sequence`, used when simulating.
+    arrivals: Vec<f64>,
+    /// Target sector of each entry of `sequence`, used when modelling rotation.
+    sectors: Vec<u32>,
+    /// Whether each entry of `sequence` reads or writes, used by the deadline scheduler.
+    ops: Vec<Op>,
+    /// Free-text note on each entry of `sequence`, saved with scenarios.
+    notes: Vec<String>,
     sequence_count: u32,
     open_panel: Panel,
     direction: Direction,
+    tie_break: TieBreak,
+    /// Requests per N-Step SCAN batch.
+    batch_size: usize,
+    deadline: DeadlineTunables,
+    /// Whether the C-SCAN / C-LOOK return jump counts toward head movement.
+    count_return_jump: bool,
+    /// Feed requests to the scheduler as they arrive instead of all at once.
+    simulate: bool,
+    seek_model: SeekModel,
+    /// Account for the platter spinning under the head, not just the seek.
+    model_rotation: bool,
+    platter: Platter,
+    detail_tab: DetailTab,
+    compare_sort: CompareColumn,
+    compare_ascending: bool,
+    /// Number of steps revealed in the plot; anything past the end shows the whole trace.
+    playback_step: usize,
+    /// Playback speed in steps per second.
+    playback_speed: f32,
+    #[serde(skip)]
+    playing: bool,
+    /// Fraction of a step accumulated since the last one was revealed.
+    #[serde(skip)]
+    playback_progress: f64,
+    /// Free-text view of `sequence`, kept in sync with the sliders.
+    #[serde(skip)]
+    sequence_text: String,
+    #[serde(skip)]
+    sequence_error: Option<String>,
+    /// Free-text view of a lookup-table seek model.
+    #[serde(skip)]
+    seek_table_text: String,
+    #[serde(skip)]
+    seek_table_error: Option<String>,
+    /// Settings of the synthetic workload generator.
+    workload: WorkloadSpec,
+    trace_format: TraceFormat,
+    /// Which blktrace event counts as a request arriving.
+    trace_event: TraceEvent,
+    csv_import: CsvImport,
+    /// Imported traces are sampled down to this many requests.
+    trace_max_requests: usize,
+    /// Spread imported sectors over the whole disk instead of dropping those past its end.
+    trace_scale_to_fit: bool,
+    /// Pasted trace text waiting to be imported.
+    #[serde(skip)]
+    trace_text: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    trace_path: String,
+    /// Outcome of the last import.
+    #[serde(skip)]
+    trace_status: Option<Result<String, String>>,
+    /// Workloads generated per Monte Carlo experiment.
+    experiment_runs: usize,
+    #[serde(skip)]
+    experiment: Vec<Trial>,
+    /// The experiment still being run, a few workloads a frame.
+    #[serde(skip)]
+    running_experiment: Option<Experiment>,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    experiment_path: String,
+    /// Outcome of the last CSV export.
+    #[serde(skip)]
+    experiment_status: Option<Result<String, String>>,
+    /// Name saved with the scenario.
+    scenario_name: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    scenario_path: String,
+    /// Scenario files opened or saved lately, most recent first.
+    #[cfg(not(target_arch = "wasm32"))]
+    recent_scenarios: Vec<String>,
+    /// Outcome of the last scenario save or open.
+    #[serde(skip)]
+    scenario_status: Option<Result<String, String>>,
+    /// Results of the algorithms for the current inputs, worked out on first use.
+    #[serde(skip)]
+    runs: RefCell<RunCache>,
+    /// Scenario files picked with the upload button, by name.
+    #[cfg(target_arch = "wasm32")]
+    #[serde(skip)]
+    uploads: crate::web::Inbox<(String, Result<String, String>)>,
+    /// Where schedule figures are saved; the extension follows the format.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    figure_path: String,
+    /// Outcome of the last figure export.
+    #[serde(skip)]
+    figure_status: Option<Result<String, String>>,
+    table_format: TableFormat,
+    /// Where tables are saved; the extension follows the format.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    table_path: String,
+    /// Outcome of the last table export.
+    #[serde(skip)]
+    table_status: Option<Result<String, String>>,
+    /// Seed of the practice problem; each seed gives a different problem.
+    quiz_seed: u64,
+    /// The typed service order.
+    #[serde(skip)]
+    quiz_order: String,
+    /// The typed total head movement.
+    #[serde(skip)]
+    quiz_total: String,
+    #[serde(skip)]
+    quiz_grade: Option<Grade>,
+    /// Why the typed answer could not be read.
+    #[serde(skip)]
+    quiz_error: Option<String>,
+    /// What the page URL hash was last set to.
+    #[cfg(target_arch = "wasm32")]
+    #[serde(skip)]
+    shared_fragment: String,
+    /// Page URL hashes the user went to since the last frame.
+    #[cfg(target_arch = "wasm32")]
+    #[serde(skip)]
+    hash_changes: crate::web::Inbox<String>,
 }
 
-#[derive(serde::Deserialize, serde::Serialize, PartialEq, Debug, Clone, Copy)]
-pub enum Direction {
-    Right,
-    Left,
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Clone, Copy)]
+enum Panel {
+    Single(Algorithm),
+    /// Every algorithm on the same workload.
+    Compare,
+    /// Waiting-time and fairness statistics of every algorithm.
+    Metrics,
+    /// Every algorithm over many generated workloads.
+    Experiment,
+    /// A random problem to work by hand, graded against the real schedule.
+    Quiz,
 }
 
-#[derive(serde::Deserialize, serde::Serialize, PartialEq)]
-enum Panel {
-    SSTF,
-    SCAN,
-    CSCAN,
-    CLOOK,
+/// What is listed below the plot of a single algorithm.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Clone, Copy)]
+enum DetailTab {
+    Steps,
+    Requests,
+    Queue,
+}
+
+/// The kind of text the trace importer expects.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Clone, Copy)]
+enum TraceFormat {
+    /// `blkparse` output or `btt -B` dumps.
+    Blkparse,
+    /// MSR Cambridge / SNIA style CSV.
+    Csv,
+}
+
+/// Column the comparison table is sorted by.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Clone, Copy)]
+enum CompareColumn {
+    Algorithm,
+    TotalSeek,
+    AverageSeek,
+    SeekTime,
+    AccessTime,
+    Reversals,
+}
+
+/// Everything the result of running an algorithm depends on.
+#[derive(PartialEq, Clone)]
+struct RunInputs {
+    requests: Vec<Request>,
+    head: u32,
+    direction: Direction,
+    geometry: Geometry,
+    tuning: Tuning,
+    simulate: bool,
+}
+
+/// What running one algorithm over the configured workload gives.
+struct Run {
+    schedule: Schedule,
+    /// `schedule.steps` with how long each took.
+    timed: Vec<TimedStep>,
+    /// Set when arrivals are simulated.
+    simulation: Option<Simulation>,
+    /// Per-request waiting time, in simulated ms or in head movements
+    /// depending on whether arrivals are simulated.
+    waits: Vec<Option<f64>>,
+}
+
+impl Run {
+    fn new(inputs: &RunInputs, algorithm: Algorithm) -> Self {
+        let scheduler = algorithm.scheduler(&inputs.tuning);
+        let seek = &inputs.tuning.seek;
+        if inputs.simulate {
+            let simulation = Simulation::run(
+                scheduler.as_ref(),
+                &inputs.requests,
+                inputs.head,
+                inputs.direction,
+                inputs.geometry,
+                seek,
+            );
+            return Self {
+                schedule: simulation.schedule.clone(),
+                timed: simulation.steps.clone(),
+                waits: metrics::simulated_waits(&simulation, inputs.requests.len()),
+                simulation: Some(simulation),
+            };
+        }
+        let schedule = scheduler.schedule(
+            &inputs.requests,
+            inputs.head,
+            inputs.direction,
+            inputs.geometry,
+        );
+        let cylinders: Vec<u32> = inputs.requests.iter().map(|r| r.cylinder).collect();
+        Self {
+            timed: time_schedule(&schedule, &inputs.requests, inputs.geometry, seek),
+            waits: metrics::step_waits(&schedule, &cylinders),
+            schedule,
+            simulation: None,
+        }
+    }
+}
+
+/// The runs of every algorithm for one set of inputs. Scheduling and
+/// simulating are too slow for long traces to redo on every frame.
+#[derive(Default)]
+struct RunCache {
+    inputs: Option<RunInputs>,
+    /// Indexed like [`Algorithm::ALL`].
+    runs: [Option<Rc<Run>>; Algorithm::ALL.len()],
+}
+
+/// One row of the comparison table.
+struct CompareRow {
+    algorithm: Algorithm,
+    name: &'static str,
+    run: Rc<Run>,
+    total: u32,
+    average: f64,
+    /// Estimated time spent seeking, in ms.
+    seek_ms: f64,
+    /// When the last request was read, counting rotation and transfer, in ms.
+    access_ms: f64,
+    reversals: usize,
 }
 
 impl Default for TemplateApp {
@@ -32,9 +308,73 @@ impl Default for TemplateApp {
             cylinder_count: 0,
             arm_position_int: 0,
             sequence: vec![0],
+            arrivals: vec![0.0],
+            sectors: vec![0],
+            ops: vec![Op::Read],
+            notes: vec![String::new()],
             sequence_count: 0,
-            open_panel: Panel::SSTF,
+            open_panel: Panel::Single(Algorithm::Sstf),
             direction: Direction::Left,
+            tie_break: TieBreak::LowerCylinder,
+            batch_size: 4,
+            deadline: DeadlineTunables::default(),
+            count_return_jump: true,
+            simulate: false,
+            seek_model: SeekModel::default(),
+            model_rotation: false,
+            platter: Platter::default(),
+            detail_tab: DetailTab::Steps,
+            compare_sort: CompareColumn::TotalSeek,
+            compare_ascending: true,
+            playback_step: usize::MAX,
+            playback_speed: 1.0,
+            playing: false,
+            playback_progress: 0.0,
+            sequence_text: String::new(),
+            sequence_error: None,
+            seek_table_text: String::new(),
+            seek_table_error: None,
+            workload: WorkloadSpec::default(),
+            trace_format: TraceFormat::Blkparse,
+            trace_event: TraceEvent::Issue,
+            csv_import: CsvImport::default(),
+            trace_max_requests: 200,
+            trace_scale_to_fit: true,
+            trace_text: String::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            trace_path: String::new(),
+            trace_status: None,
+            experiment_runs: 50,
+            experiment: Vec::new(),
+            running_experiment: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            experiment_path: "experiment.csv".to_owned(),
+            experiment_status: None,
+            scenario_name: String::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            scenario_path: "scenario.json".to_owned(),
+            #[cfg(not(target_arch = "wasm32"))]
+            recent_scenarios: Vec::new(),
+            scenario_status: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            figure_path: "schedule.svg".to_owned(),
+            figure_status: None,
+            table_format: TableFormat::Markdown,
+            #[cfg(not(target_arch = "wasm32"))]
+            table_path: "table.md".to_owned(),
+            table_status: None,
+            quiz_seed: 0,
+            quiz_order: String::new(),
+            quiz_total: String::new(),
+            quiz_grade: None,
+            quiz_error: None,
+            runs: RefCell::default(),
+            #[cfg(target_arch = "wasm32")]
+            uploads: Default::default(),
+            #[cfg(target_arch = "wasm32")]
+            shared_fragment: String::new(),
+            #[cfg(target_arch = "wasm32")]
+            hash_changes: Default::default(),
         }
     }
 }
@@ -48,17 +388,22 @@ impl TemplateApp {
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
 
-        if let Some(storage) = cc.storage {
-            return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
-        }
-
-        Default::default()
-    }
-
-    pub fn ordered_by_closeness(input: &Vec<u32>, base: u32) -> Vec<u32> {
-        let mut result = input.clone();
-        result.sort_by_key(|&x| (x as i32 - base as i32).abs());
-        result
+        let app: Self = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+            .unwrap_or_default();
+
+        // A shared link wins over whatever was open last time, and so does
+        // one pasted into the address bar later on.
+        #[cfg(target_arch = "wasm32")]
+        let app = {
+            let mut app = app;
+            app.open_page_link(&cc.integration_info.web_info.location);
+            crate::web::watch_hash(app.hash_changes.clone(), cc.egui_ctx.clone());
+            app
+        };
+
+        app
     }
 
     pub fn arrow_direction(prev: f64, cur: f64) -> egui_plot::MarkerShape {
@@ -71,198 +416,1411 @@ impl TemplateApp {
         }
     }
 
-    pub fn clook(vec: &Vec<u32>, head_value: u32, direction: Direction) -> Vec<u32> {
-        let mut sorted_vec = vec.clone();
-        sorted_vec.sort(); // Sort the vector
-        let mut output: Vec<u32> = Vec::new();
+    fn geometry(&self) -> Geometry {
+        Geometry {
+            max_cylinder: self.cylinder_count,
+            platter: self.model_rotation.then_some(self.platter),
+        }
+    }
 
-        match direction {
-            Direction::Left => {
-                let mut right: Vec<u32> = Vec::new();
-                let mut left: Vec<u32> = Vec::new();
+    /// The configured sequence together with its arrival times and sectors.
+    fn requests(&self) -> Vec<Request> {
+        self.sequence
+            .iter()
+            .enumerate()
+            .map(|(i, &cylinder)| {
+                Request::arriving_at(cylinder, self.arrivals.get(i).copied().unwrap_or(0.0))
+                    .on_sector(self.sectors.get(i).copied().unwrap_or(0))
+                    .with_op(self.ops.get(i).copied().unwrap_or_default())
+            })
+            .collect()
+    }
 
-                for &val in sorted_vec.iter() {
-                    if output.is_empty() {
-                        if val < head_value {
-                            left.push(val);
-                        } else {
-                            right.push(val);
-                        }
-                    }
-                }
+    /// Replaces the configured sequence with `requests`.
+    fn load_requests(&mut self, requests: &[Request]) {
+        self.sequence = requests.iter().map(|request| request.cylinder).collect();
+        self.arrivals = requests.iter().map(|request| request.arrival_ms).collect();
+        self.sectors = requests.iter().map(|request| request.sector).collect();
+        self.ops = requests.iter().map(|request| request.op).collect();
+        self.notes.clear();
+    }
 
-                right.sort();
-                right.reverse();
+    /// The current disk, sequence and options as a scenario.
+    fn scenario(&self) -> Scenario {
+        let algorithms = match self.open_panel {
+            Panel::Single(algorithm) => vec![algorithm],
+            Panel::Compare | Panel::Metrics | Panel::Experiment | Panel::Quiz => {
+                Algorithm::ALL.to_vec()
+            }
+        };
+        let mut notes = self.notes.clone();
+        notes.resize(self.sequence.len(), String::new());
+        Scenario {
+            name: self.scenario_name.clone(),
+            geometry: self.geometry(),
+            head: self.arm_position_int,
+            direction: self.direction,
+            requests: self.requests(),
+            notes,
+            algorithms,
+            tuning: self.tuning(),
+            count_return_jump: self.count_return_jump,
+            simulate: self.simulate,
+        }
+    }
 
-                left.sort();
-                left.reverse();
+    /// Replaces the disk, sequence and options with those of `scenario`.
+    fn apply_scenario(&mut self, scenario: Scenario) {
+        self.scenario_name = scenario.name;
+        self.cylinder_count = scenario.geometry.max_cylinder;
+        self.model_rotation = scenario.geometry.platter.is_some();
+        if let Some(platter) = scenario.geometry.platter {
+            self.platter = platter;
+        }
+        self.arm_position_int = scenario.head;
+        self.direction = scenario.direction;
+        self.load_requests(&scenario.requests);
+        self.notes = scenario.notes;
+        match scenario.algorithms.as_slice() {
+            [] => {}
+            [algorithm] => self.open_panel = Panel::Single(*algorithm),
+            _ => self.open_panel = Panel::Compare,
+        }
+        self.tie_break = scenario.tuning.tie_break;
+        self.seek_model = scenario.tuning.seek;
+        self.batch_size = scenario.tuning.batch_size;
+        self.deadline = scenario.tuning.deadline;
+        self.count_return_jump = scenario.count_return_jump;
+        self.simulate = scenario.simulate;
+        self.sequence_error = None;
+        self.seek_table_error = None;
+        self.playback_step = usize::MAX;
+        self.playing = false;
+    }
 
-                output.append(&mut left);
-                output.append(&mut right);
+    /// The disk, head, direction, sequence and open algorithm as a shareable link.
+    #[cfg(target_arch = "wasm32")]
+    fn link(&self) -> Link {
+        Link {
+            cylinders: self.cylinder_count + 1,
+            head: self.arm_position_int,
+            direction: self.direction,
+            requests: self.requests(),
+            algorithm: match self.open_panel {
+                Panel::Single(algorithm) => Some(algorithm),
+                Panel::Compare | Panel::Metrics | Panel::Experiment | Panel::Quiz => None,
+            },
+        }
+    }
+
+    /// Opens the workload of `link`, leaving the other options as they are.
+    #[cfg(target_arch = "wasm32")]
+    fn apply_link(&mut self, link: Link) {
+        self.cylinder_count = link.cylinders - 1;
+        self.arm_position_int = link.head;
+        self.direction = link.direction;
+        self.load_requests(&link.requests);
+        self.simulate |= link.requests.iter().any(|request| request.arrival_ms > 0.0);
+        self.model_rotation |= link.requests.iter().any(|request| request.sector > 0);
+        self.open_panel = link.algorithm.map_or(Panel::Compare, Panel::Single);
+        self.sequence_error = None;
+        self.playback_step = usize::MAX;
+        self.playing = false;
+    }
+
+    /// Opens the link in the hash or query of the page URL, if there is one.
+    #[cfg(target_arch = "wasm32")]
+    fn open_page_link(&mut self, location: &eframe::Location) {
+        self.open_link(Link::parse(&location.hash).or_else(|| Link::parse(&location.query)));
+    }
+
+    /// Opens the link in the last page URL hash the user went to, unless it
+    /// is the one the app wrote itself.
+    #[cfg(target_arch = "wasm32")]
+    fn follow_hash_changes(&mut self) {
+        let hash = self.hash_changes.borrow_mut().drain(..).last();
+        if let Some(hash) = hash.filter(|hash| hash.trim_start_matches('#') != self.shared_fragment)
+        {
+            self.open_link(Link::parse(&hash));
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn open_link(&mut self, link: Option<Result<Link, String>>) {
+        match link {
+            Some(Ok(link)) => self.apply_link(link),
+            Some(Err(err)) => self.scenario_status = Some(Err(format!("link: {err}"))),
+            None => {}
+        }
+    }
+
+    /// The whole of `schedule` as a standalone figure.
+    fn figure(&self, algorithm: Algorithm, schedule: &Schedule) -> Figure {
+        let color = algorithm_color(algorithm);
+        figure::schedule_figure(
+            self.scheduler(algorithm).name(),
+            schedule,
+            self.cylinder_count,
+            self.count_return_jump,
+            [color.r(), color.g(), color.b()],
+        )
+    }
+
+    /// Buttons that draw `schedule` to an SVG or PNG file.
+    fn figure_export(&mut self, ui: &mut egui::Ui, algorithm: Algorithm, schedule: &Schedule) {
+        ui.horizontal_wrapped(|ui| {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.figure_path)
+                        .hint_text("figure file")
+                        .desired_width(160.0),
+                );
+                let save = |path: std::path::PathBuf, bytes: Result<Vec<u8>, String>| {
+                    let path = path.display().to_string();
+                    bytes
+                        .and_then(|bytes| {
+                            std::fs::write(&path, bytes)
+                                .map_err(|err| format!("cannot write {path}: {err}"))
+                        })
+                        .map(|()| format!("saved to {path}"))
+                };
+                let path = std::path::Path::new(&self.figure_path);
+                if ui.button("Save SVG").clicked() {
+                    let svg = self.figure(algorithm, schedule).to_svg().into_bytes();
+                    self.figure_status = Some(save(path.with_extension("svg"), Ok(svg)));
+                }
+                if ui.button("Save PNG").clicked() {
+                    let png = self.figure(algorithm, schedule).to_png();
+                    self.figure_status = Some(save(path.with_extension("png"), png));
+                }
+            }
+            #[cfg(target_arch = "wasm32")]
+            if ui.button("Download SVG").clicked() {
+                let svg = self.figure(algorithm, schedule).to_svg();
+                let file_name = format!("{}.svg", crate::scenario::key(algorithm));
+                self.figure_status = Some(
+                    crate::web::download(&file_name, "image/svg+xml", svg.as_bytes())
+                        .map(|()| "downloaded".to_owned()),
+                );
             }
 
-            Direction::Right => {
-                let mut right: Vec<u32> = Vec::new();
-                let mut left: Vec<u32> = Vec::new();
+            match &self.figure_status {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(err)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, err);
+                }
+                None => {}
+            }
+        });
+    }
 
-                for &val in sorted_vec.iter() {
-                    if output.is_empty() {
-                        if val < head_value {
-                            left.push(val);
-                        } else {
-                            right.push(val);
-                        }
+    /// Format picker and buttons that copy or save the table built by `table`;
+    /// `name` names downloaded files. The table is only built when needed.
+    fn table_export(&mut self, ui: &mut egui::Ui, table: impl Fn(&Self) -> Table, name: &str) {
+        ui.horizontal_wrapped(|ui| {
+            egui::ComboBox::from_id_source(("table_format", name))
+                .selected_text(self.table_format.label())
+                .show_ui(ui, |ui| {
+                    for format in TableFormat::ALL {
+                        ui.selectable_value(&mut self.table_format, format, format.label());
                     }
+                });
+            if ui.button("Copy Table").clicked() {
+                let text = table(self).render(self.table_format);
+                ui.output_mut(|output| output.copied_text = text);
+                self.table_status = Some(Ok("copied to the clipboard".to_owned()));
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.table_path)
+                        .hint_text("table file")
+                        .desired_width(160.0),
+                );
+                if ui.button("Save Table").clicked() {
+                    let path = std::path::Path::new(&self.table_path)
+                        .with_extension(self.table_format.extension())
+                        .display()
+                        .to_string();
+                    self.table_status = Some(
+                        std::fs::write(&path, table(self).render(self.table_format))
+                            .map(|()| format!("saved to {path}"))
+                            .map_err(|err| format!("cannot write {path}: {err}")),
+                    );
                 }
+            }
+            #[cfg(target_arch = "wasm32")]
+            if ui.button("Download Table").clicked() {
+                let file_name = format!("{name}.{}", self.table_format.extension());
+                self.table_status = Some(
+                    crate::web::download(
+                        &file_name,
+                        "text/plain",
+                        table(self).render(self.table_format).as_bytes(),
+                    )
+                    .map(|()| "downloaded".to_owned()),
+                );
+            }
 
-                right.sort();
-                left.sort();
+            match &self.table_status {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(err)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, err);
+                }
+                None => {}
+            }
+        });
+    }
+
+    /// Opens the scenario in `text`, read from `source`.
+    fn open_scenario_text(&mut self, text: &str, source: &str) -> Result<(), String> {
+        let scenario = Scenario::from_json(text).map_err(|err| format!("{source}: {err}"))?;
+        self.apply_scenario(scenario);
+        self.scenario_status = Some(Ok(format!("opened {source}")));
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_scenario(&mut self, path: &str) {
+        let opened = std::fs::read_to_string(path)
+            .map_err(|err| format!("cannot read {path}: {err}"))
+            .and_then(|text| self.open_scenario_text(&text, path));
+        match opened {
+            Ok(()) => self.remember_scenario(path),
+            Err(err) => self.scenario_status = Some(Err(err)),
+        }
+    }
 
-                output.append(&mut right);
-                output.append(&mut left);
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_scenario(&mut self, path: &str) {
+        let text = format!("{}\n", self.scenario().to_json());
+        match std::fs::write(path, text) {
+            Ok(()) => {
+                self.scenario_status = Some(Ok(format!("saved to {path}")));
+                self.remember_scenario(path);
             }
+            Err(err) => self.scenario_status = Some(Err(format!("cannot write {path}: {err}"))),
         }
-        output
     }
 
-    pub fn scan(
-        vec: &Vec<u32>,
-        head_value: u32,
-        direction: Direction,
-        cylinder_count: u32,
-    ) -> Vec<u32> {
-        let mut sorted_vec = vec.clone();
-        sorted_vec.sort(); // Sort the vector
-        let mut output: Vec<u32> = Vec::new();
+    #[cfg(not(target_arch = "wasm32"))]
+    fn remember_scenario(&mut self, path: &str) {
+        const MAX_RECENT: usize = 8;
+        self.recent_scenarios.retain(|recent| recent != path);
+        self.recent_scenarios.insert(0, path.to_owned());
+        self.recent_scenarios.truncate(MAX_RECENT);
+        self.scenario_path = path.to_owned();
+    }
 
-        match direction {
-            Direction::Left => {
-                let mut right: Vec<u32> = Vec::new();
-                let mut left: Vec<u32> = Vec::new();
+    /// The scenario entries of the File menu.
+    fn scenario_menu(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.text_edit_singleline(&mut self.scenario_name);
+        });
 
-                for &val in sorted_vec.iter() {
-                    if output.is_empty() {
-                        if val < head_value {
-                            left.push(val);
-                        } else {
-                            right.push(val);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            ui.horizontal(|ui| {
+                ui.label("File");
+                ui.text_edit_singleline(&mut self.scenario_path);
+            });
+            if ui.button("Save Scenario").clicked() {
+                let path = self.scenario_path.clone();
+                self.save_scenario(&path);
+                ui.close_menu();
+            }
+            if ui.button("Open Scenario").clicked() {
+                let path = self.scenario_path.clone();
+                self.open_scenario(&path);
+                ui.close_menu();
+            }
+            ui.add_enabled_ui(!self.recent_scenarios.is_empty(), |ui| {
+                ui.menu_button("Open Recent", |ui| {
+                    for path in self.recent_scenarios.clone() {
+                        if ui.button(&path).clicked() {
+                            self.open_scenario(&path);
+                            ui.close_menu();
                         }
                     }
+                });
+            });
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if ui.button("Upload Scenario").clicked() {
+            let uploaded = crate::web::upload(
+                ".json,application/json",
+                self.uploads.clone(),
+                ui.ctx().clone(),
+            );
+            if let Err(err) = uploaded {
+                self.scenario_status = Some(Err(err));
+            }
+            ui.close_menu();
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if ui.button("Download Scenario").clicked() {
+            let text = format!("{}\n", self.scenario().to_json());
+            self.scenario_status = Some(
+                crate::web::download(
+                    &self.scenario_file_name(),
+                    "application/json",
+                    text.as_bytes(),
+                )
+                .map(|()| "downloaded".to_owned()),
+            );
+            ui.close_menu();
+        }
+
+        ui.label("Drop a scenario .json file on the window to open it.");
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn scenario_file_name(&self) -> String {
+        let name: String = self
+            .scenario_name
+            .trim()
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
                 }
+            })
+            .collect();
+        if name.is_empty() {
+            "scenario.json".to_owned()
+        } else {
+            format!("{name}.json")
+        }
+    }
 
-                right.sort();
+    /// Opens scenario files dropped on the window.
+    fn open_dropped_scenarios(&mut self, ctx: &egui::Context) {
+        let dropped = ctx.input(|input| input.raw.dropped_files.clone());
+        for file in dropped {
+            if let Some(bytes) = &file.bytes {
+                let text = String::from_utf8_lossy(bytes);
+                if let Err(err) = self.open_scenario_text(&text, &file.name) {
+                    self.scenario_status = Some(Err(err));
+                }
+                continue;
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(path) = &file.path {
+                self.open_scenario(&path.display().to_string());
+            }
+        }
+    }
+
+    /// Opens the scenario files that finished uploading since the last frame.
+    #[cfg(target_arch = "wasm32")]
+    fn open_uploaded_scenarios(&mut self) {
+        let uploads = std::mem::take(&mut *self.uploads.borrow_mut());
+        for (name, text) in uploads {
+            if let Err(err) = text.and_then(|text| self.open_scenario_text(&text, &name)) {
+                self.scenario_status = Some(Err(err));
+            }
+        }
+    }
 
-                left.sort();
-                left.reverse();
+    fn tuning(&self) -> Tuning {
+        Tuning {
+            tie_break: self.tie_break,
+            seek: self.seek_model.clone(),
+            batch_size: self.batch_size,
+            deadline: self.deadline,
+        }
+    }
 
-                output.append(&mut left);
-                if !sorted_vec.contains(&0) {
-                    output.push(0);
+    fn scheduler(&self, algorithm: Algorithm) -> Box<dyn DiskScheduler> {
+        algorithm.scheduler(&self.tuning())
+    }
+
+    /// Runs `algorithm` over the configured disk and sequence, with requests
+    /// arriving over time when simulating. The result is reused until one of
+    /// its inputs changes.
+    fn run(&self, algorithm: Algorithm) -> Rc<Run> {
+        let inputs = RunInputs {
+            requests: self.requests(),
+            head: self.arm_position_int,
+            direction: self.direction,
+            geometry: self.geometry(),
+            tuning: self.tuning(),
+            simulate: self.simulate,
+        };
+        let mut cache = self.runs.borrow_mut();
+        if cache.inputs.as_ref() != Some(&inputs) {
+            *cache = RunCache {
+                inputs: Some(inputs),
+                ..Default::default()
+            };
+        }
+        let RunCache { inputs, runs } = &mut *cache;
+        let inputs = inputs.as_ref().expect("inputs were just set");
+        runs[algorithm_index(algorithm)]
+            .get_or_insert_with(|| Rc::new(Run::new(inputs, algorithm)))
+            .clone()
+    }
+
+    /// Total head movement of `algorithm` and the estimated time it takes.
+    fn seek_summary(&self, algorithm: Algorithm) -> String {
+        let run = self.run(algorithm);
+        let mut summary = format!(
+            "{} cylinders, {:.2} ms",
+            run.schedule.total_movement(self.count_return_jump),
+            self.seek_model
+                .total_ms(&run.schedule, self.count_return_jump)
+        );
+        if self.model_rotation {
+            summary.push_str(&format!(
+                ", done at {:.2} ms with rotation",
+                finish_ms(&run.timed)
+            ));
+        }
+        summary
+    }
+
+    /// Runs every algorithm and orders the results by the selected table column.
+    fn compare_rows(&self) -> Vec<CompareRow> {
+        let requests = self.sequence.len().max(1) as f64;
+        let mut rows: Vec<CompareRow> = Algorithm::ALL
+            .into_iter()
+            .map(|algorithm| {
+                let run = self.run(algorithm);
+                let total = run.schedule.total_movement(self.count_return_jump);
+                CompareRow {
+                    algorithm,
+                    name: self.scheduler(algorithm).name(),
+                    total,
+                    average: total as f64 / requests,
+                    seek_ms: self
+                        .seek_model
+                        .total_ms(&run.schedule, self.count_return_jump),
+                    access_ms: finish_ms(&run.timed),
+                    reversals: run.schedule.direction_reversals(),
+                    run,
                 }
-                output.append(&mut right);
+            })
+            .collect();
+
+        rows.sort_by(|a, b| {
+            let ord = match self.compare_sort {
+                CompareColumn::Algorithm => a.name.cmp(b.name),
+                CompareColumn::TotalSeek => a.total.cmp(&b.total),
+                CompareColumn::AverageSeek => a.average.total_cmp(&b.average),
+                CompareColumn::SeekTime => a.seek_ms.total_cmp(&b.seek_ms),
+                CompareColumn::AccessTime => a.access_ms.total_cmp(&b.access_ms),
+                CompareColumn::Reversals => a.reversals.cmp(&b.reversals),
+            };
+            if self.compare_ascending {
+                ord
+            } else {
+                ord.reverse()
             }
+        });
+        rows
+    }
 
-            Direction::Right => {
-                let mut right: Vec<u32> = Vec::new();
-                let mut left: Vec<u32> = Vec::new();
+    /// Header cell that sorts the comparison table by `column`, toggling the
+    /// order when it is already the sort column.
+    fn compare_header(&mut self, ui: &mut egui::Ui, column: CompareColumn, label: &str) {
+        let text = if self.compare_sort != column {
+            label.to_owned()
+        } else if self.compare_ascending {
+            format!("{label} ⬆")
+        } else {
+            format!("{label} ⬇")
+        };
+        if ui.button(egui::RichText::new(text).strong()).clicked() {
+            if self.compare_sort == column {
+                self.compare_ascending = !self.compare_ascending;
+            } else {
+                self.compare_sort = column;
+                self.compare_ascending = true;
+            }
+        }
+    }
+
+    /// Text box accepting a whole sequence at once, e.g. pasted from a textbook problem.
+    fn sequence_text_edit(&mut self, ui: &mut egui::Ui) {
+        let response = ui
+            .add(
+                egui::TextEdit::multiline(&mut self.sequence_text)
+                    .hint_text("98, 183, 37, 122 or 98:12@0, w183:40@2.5, 37@4")
+                    .desired_rows(2)
+                    .desired_width(f32::INFINITY),
+            )
+            .on_hover_text(
+                "Cylinders separated by commas, spaces or new lines, optionally with \
+                 a sector and an arrival time in ms as cylinder:sector@time. \
+                 Prefix a request with w to make it a write.",
+            );
+
+        if response.changed() {
+            let max = self.cylinder_count;
+            let track = self.platter.sectors_per_track;
+            let parsed = parse_requests(&self.sequence_text).and_then(|requests| {
+                if let Some(request) = requests.iter().find(|request| request.cylinder > max) {
+                    return Err(format!(
+                        "cylinder {} is outside 0..={max}",
+                        request.cylinder
+                    ));
+                }
+                if let Some(request) = requests.iter().find(|request| request.sector >= track) {
+                    return Err(format!("sector {} is outside 0..{track}", request.sector));
+                }
+                Ok(requests)
+            });
+            match parsed {
+                Ok(requests) => {
+                    // Edits in place keep their notes.
+                    let notes = std::mem::take(&mut self.notes);
+                    self.load_requests(&requests);
+                    self.notes = notes;
+                    self.sequence_error = None;
+                }
+                Err(err) => self.sequence_error = Some(err),
+            }
+        } else if !response.has_focus() && self.sequence_error.is_none() {
+            self.sequence_text = format_requests(&self.requests(), ", ");
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("Copy Sequence").clicked() {
+                let text = self.sequence_text.clone();
+                ui.output_mut(|o| o.copied_text = text);
+            }
+            if let Some(err) = &self.sequence_error {
+                ui.colored_label(ui.visuals().error_fg_color, err);
+            }
+        });
+    }
+
+    /// Picks the seek model and edits its parameters.
+    fn seek_model_settings(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_label("Seek Model")
+            .selected_text(self.seek_model.name())
+            .show_ui(ui, |ui| {
+                ui.style_mut().wrap = Some(false);
+                ui.set_min_width(60.0);
+                for model in [
+                    SeekModel::linear(),
+                    SeekModel::sqrt_linear(),
+                    SeekModel::table(),
+                ] {
+                    let selected =
+                        std::mem::discriminant(&model) == std::mem::discriminant(&self.seek_model);
+                    if ui.selectable_label(selected, model.name()).clicked() && !selected {
+                        self.seek_model = model;
+                        self.seek_table_text.clear();
+                        self.seek_table_error = None;
+                    }
+                }
+            });
 
-                for &val in sorted_vec.iter() {
-                    if output.is_empty() {
-                        if val < head_value {
-                            left.push(val);
-                        } else {
-                            right.push(val);
+        match &mut self.seek_model {
+            SeekModel::Linear {
+                overhead_ms,
+                per_cylinder_ms,
+            } => {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(overhead_ms)
+                            .clamp_range(0.0..=100.0)
+                            .speed(0.05)
+                            .suffix(" ms"),
+                    );
+                    ui.label("+");
+                    ui.add(
+                        egui::DragValue::new(per_cylinder_ms)
+                            .clamp_range(0.0..=10.0)
+                            .speed(0.005)
+                            .suffix(" ms/cylinder"),
+                    );
+                });
+            }
+            SeekModel::SqrtLinear {
+                settle_ms,
+                sqrt_ms,
+                coast_from,
+                per_cylinder_ms,
+            } => {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(settle_ms)
+                            .clamp_range(0.0..=100.0)
+                            .speed(0.05)
+                            .suffix(" ms"),
+                    )
+                    .on_hover_text("Settle time");
+                    ui.label("+");
+                    ui.add(
+                        egui::DragValue::new(sqrt_ms)
+                            .clamp_range(0.0..=10.0)
+                            .speed(0.005)
+                            .suffix(" ms·√d"),
+                    )
+                    .on_hover_text("Acceleration phase");
+                    ui.label("up to");
+                    ui.add(
+                        egui::DragValue::new(coast_from)
+                            .clamp_range(0..=u32::MAX)
+                            .suffix(" cylinders"),
+                    );
+                    ui.label("then");
+                    ui.add(
+                        egui::DragValue::new(per_cylinder_ms)
+                            .clamp_range(0.0..=10.0)
+                            .speed(0.001)
+                            .suffix(" ms/cylinder"),
+                    )
+                    .on_hover_text("Coasting at full speed");
+                });
+            }
+            SeekModel::Table(points) => {
+                ui.vertical(|ui| {
+                    let response = ui
+                        .add(
+                            egui::TextEdit::singleline(&mut self.seek_table_text)
+                                .hint_text("1:1, 10:2.2, 100:5, 1000:12"),
+                        )
+                        .on_hover_text("Seek times in ms for a few distances, as distance:ms");
+                    if response.changed() {
+                        match parse_seek_table(&self.seek_table_text) {
+                            Ok(parsed) => {
+                                *points = parsed;
+                                self.seek_table_error = None;
+                            }
+                            Err(err) => self.seek_table_error = Some(err),
                         }
+                    } else if !response.has_focus() && self.seek_table_error.is_none() {
+                        let pairs: Vec<String> = points
+                            .iter()
+                            .map(|(distance, ms)| format!("{distance}:{ms}"))
+                            .collect();
+                        self.seek_table_text = pairs.join(", ");
+                    }
+                    if let Some(err) = &self.seek_table_error {
+                        ui.colored_label(ui.visuals().error_fg_color, err);
+                    }
+                });
+            }
+        }
+    }
+
+    /// Draws a reproducible random sequence from one of several distributions.
+    fn workload_generator(&mut self, ui: &mut egui::Ui) {
+        let max = self.cylinder_count;
+        let spec = &mut self.workload;
+        egui::ComboBox::from_label("Distribution")
+            .selected_text(spec.distribution.name())
+            .show_ui(ui, |ui| {
+                for preset in Distribution::presets(max) {
+                    let selected = std::mem::discriminant(&preset)
+                        == std::mem::discriminant(&spec.distribution);
+                    if ui.selectable_label(selected, preset.name()).clicked() && !selected {
+                        spec.distribution = preset;
                     }
                 }
+            });
+
+        ui.horizontal_wrapped(|ui| match &mut spec.distribution {
+            Distribution::Uniform => {}
+            Distribution::Hotspot { center, spread } => {
+                ui.label("center");
+                ui.add(egui::DragValue::new(center).clamp_range(0..=max));
+                ui.label("spread");
+                ui.add(egui::DragValue::new(spread).clamp_range(0.0..=max as f64));
+            }
+            Distribution::Zipf { exponent } => {
+                ui.label("exponent");
+                ui.add(
+                    egui::DragValue::new(exponent)
+                        .clamp_range(0.0..=5.0)
+                        .speed(0.01),
+                );
+            }
+            Distribution::SequentialRuns { run_length } => {
+                ui.label("run length");
+                ui.add(egui::DragValue::new(run_length).clamp_range(1..=1000));
+            }
+            Distribution::Bimodal {
+                first,
+                second,
+                spread,
+                first_share,
+            } => {
+                ui.label("peaks");
+                ui.add(egui::DragValue::new(first).clamp_range(0..=max));
+                ui.add(egui::DragValue::new(second).clamp_range(0..=max));
+                ui.label("spread");
+                ui.add(egui::DragValue::new(spread).clamp_range(0.0..=max as f64));
+                ui.label("first share");
+                ui.add(
+                    egui::DragValue::new(first_share)
+                        .clamp_range(0.0..=1.0)
+                        .speed(0.01),
+                );
+            }
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label("count");
+            ui.add(egui::DragValue::new(&mut spec.count).clamp_range(1..=1000));
+            ui.label("seed");
+            ui.add(egui::DragValue::new(&mut spec.seed));
+
+            let mut poisson = spec.arrival_rate.is_some();
+            ui.checkbox(&mut poisson, "Poisson Arrivals");
+            let mut rate = spec.arrival_rate.unwrap_or(0.1);
+            if poisson {
+                ui.add(
+                    egui::DragValue::new(&mut rate)
+                        .clamp_range(0.001..=100.0)
+                        .speed(0.005)
+                        .suffix(" req/ms"),
+                );
+            }
+            spec.arrival_rate = poisson.then_some(rate);
+        });
+
+        if ui.button("Generate").clicked() {
+            let requests = workload::generate(&self.workload, self.geometry());
+            self.load_requests(&requests);
+            self.simulate |= self.workload.arrival_rate.is_some();
+        }
+    }
+
+    /// Loads a recorded blktrace as the sequence shared by every scheduler.
+    fn trace_import(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.trace_format, TraceFormat::Blkparse, "blkparse");
+            ui.selectable_value(&mut self.trace_format, TraceFormat::Csv, "CSV");
+        });
 
-                right.sort();
+        match self.trace_format {
+            TraceFormat::Blkparse => {
+                egui::ComboBox::from_label("Arrival Event")
+                    .selected_text(match self.trace_event {
+                        TraceEvent::Queue => "Q (queued)",
+                        TraceEvent::Issue => "D (issued)",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.trace_event, TraceEvent::Queue, "Q (queued)");
+                        ui.selectable_value(&mut self.trace_event, TraceEvent::Issue, "D (issued)");
+                    });
+            }
+            TraceFormat::Csv => csv_settings(ui, &mut self.csv_import),
+        }
 
-                left.sort();
-                left.reverse();
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.trace_scale_to_fit, "Scale To Fit")
+                .on_hover_text(
+                    "Spread the trace over every cylinder instead of dropping \
+                     sectors past the last one",
+                );
+            ui.label("Keep at most");
+            ui.add(
+                egui::DragValue::new(&mut self.trace_max_requests)
+                    .clamp_range(1..=MAX_REQUESTS)
+                    .suffix(" requests"),
+            )
+            .on_hover_text("Larger traces are sampled evenly");
+        });
 
-                output.append(&mut right);
-                if !sorted_vec.contains(&cylinder_count) {
-                    output.push(cylinder_count);
+        ui.add(
+            egui::TextEdit::multiline(&mut self.trace_text)
+                .hint_text(match self.trace_format {
+                    TraceFormat::Blkparse => "Paste blkparse output or btt -B block dumps",
+                    TraceFormat::Csv => {
+                        "Timestamp,Hostname,DiskNumber,Type,Offset,Size,ResponseTime"
+                    }
+                })
+                .desired_rows(3)
+                .desired_width(f32::INFINITY),
+        );
+
+        let mut import = None;
+        ui.horizontal(|ui| {
+            if ui.button("Import Pasted").clicked() {
+                import = Some(Ok(self.trace_text.clone()));
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                ui.add(egui::TextEdit::singleline(&mut self.trace_path).hint_text("trace file"));
+                if ui.button("Import File").clicked() {
+                    import = Some(
+                        std::fs::read_to_string(&self.trace_path)
+                            .map_err(|err| format!("cannot read {}: {err}", self.trace_path)),
+                    );
                 }
-                output.append(&mut left);
             }
+        });
+
+        if let Some(text) = import {
+            let mapping = SectorMapping {
+                max_cylinder: self.cylinder_count,
+                sectors_per_track: self.platter.sectors_per_track,
+                scale_to_fit: self.trace_scale_to_fit,
+            };
+            self.trace_status = Some(
+                text.and_then(|text| match self.trace_format {
+                    TraceFormat::Blkparse => parse_blkparse(&text, self.trace_event, mapping),
+                    TraceFormat::Csv => parse_csv(&text, &self.csv_import, mapping),
+                })
+                .map(|requests| {
+                    let found = requests.len();
+                    let requests = trace::sample(requests, self.trace_max_requests);
+                    self.load_requests(&requests);
+                    // Replaying the trace only makes sense with its timing.
+                    self.simulate |= requests.iter().any(|r| r.arrival_ms > 0.0);
+                    if requests.len() < found {
+                        format!("Imported {} of {found} requests", requests.len())
+                    } else {
+                        format!("Imported {found} requests")
+                    }
+                }),
+            );
+        }
+        match &self.trace_status {
+            Some(Ok(status)) => {
+                ui.label(status);
+            }
+            Some(Err(err)) => {
+                ui.colored_label(ui.visuals().error_fg_color, err);
+            }
+            None => {}
         }
-        output
     }
 
-    pub fn cscan(
-        vec: &Vec<u32>,
-        head_value: u32,
-        direction: Direction,
-        cylinder_count: u32,
-    ) -> Vec<u32> {
-        let mut sorted_vec = vec.clone();
-        sorted_vec.sort(); // Sort the vector
-        let mut output: Vec<u32> = Vec::new();
+    /// Expiry times and batching knobs of the deadline scheduler.
+    fn deadline_settings(&mut self, ui: &mut egui::Ui) {
+        let deadline = &mut self.deadline;
+        ui.horizontal_wrapped(|ui| {
+            ui.label("read expire");
+            ui.add(
+                egui::DragValue::new(&mut deadline.read_expire_ms)
+                    .clamp_range(0.0..=60_000.0)
+                    .speed(1.0)
+                    .suffix(" ms"),
+            );
+            ui.label("write expire");
+            ui.add(
+                egui::DragValue::new(&mut deadline.write_expire_ms)
+                    .clamp_range(0.0..=60_000.0)
+                    .speed(1.0)
+                    .suffix(" ms"),
+            );
+            ui.label("fifo_batch");
+            ui.add(egui::DragValue::new(&mut deadline.fifo_batch).clamp_range(1..=256));
+            ui.label("writes_starved");
+            ui.add(egui::DragValue::new(&mut deadline.writes_starved).clamp_range(0..=16));
+        });
+    }
 
-        match direction {
-            Direction::Left => {
-                let mut right: Vec<u32> = Vec::new();
-                let mut left: Vec<u32> = Vec::new();
+    /// Advances an ongoing playback by the time elapsed since the last frame.
+    fn tick_playback(&mut self, ctx: &egui::Context, step_count: usize) {
+        if !self.playing {
+            return;
+        }
 
-                for &val in sorted_vec.iter() {
-                    if output.is_empty() {
-                        if val < head_value {
-                            left.push(val);
-                        } else {
-                            right.push(val);
-                        }
-                    }
+        self.playback_progress += ctx.input(|i| i.stable_dt) as f64 * self.playback_speed as f64;
+        while self.playback_progress >= 1.0 && self.playback_step < step_count {
+            self.playback_step += 1;
+            self.playback_progress -= 1.0;
+        }
+        if self.playback_step >= step_count {
+            self.playing = false;
+        }
+        ctx.request_repaint();
+    }
+
+    /// Runs the next few workloads of an ongoing experiment, keeping each
+    /// frame to about [`EXPERIMENT_REQUESTS_PER_FRAME`] generated requests.
+    fn tick_experiment(&mut self, ctx: &egui::Context) {
+        let Some(experiment) = &mut self.running_experiment else {
+            return;
+        };
+
+        self.experiment
+            .extend(experiment.advance(EXPERIMENT_REQUESTS_PER_FRAME));
+        if experiment.is_done() {
+            self.running_experiment = None;
+        }
+        ctx.request_repaint();
+    }
+
+    /// Play/pause/step controls plus the state of the head at the current step.
+    fn playback_controls(&mut self, ui: &mut egui::Ui, schedule: &Schedule) {
+        let step_count = schedule.steps.len();
+        self.playback_step = self.playback_step.min(step_count);
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.playback_step > 0, egui::Button::new("⏮"))
+                .on_hover_text("Step back")
+                .clicked()
+            {
+                self.playing = false;
+                self.playback_step -= 1;
+            }
+
+            let play_label = if self.playing { "⏸" } else { "▶" };
+            if ui
+                .button(play_label)
+                .on_hover_text("Play / pause")
+                .clicked()
+            {
+                if !self.playing && self.playback_step >= step_count {
+                    self.playback_step = 0;
                 }
+                self.playing = !self.playing;
+                self.playback_progress = 0.0;
+            }
+
+            if ui
+                .add_enabled(self.playback_step < step_count, egui::Button::new("⏭"))
+                .on_hover_text("Step forward")
+                .clicked()
+            {
+                self.playing = false;
+                self.playback_step += 1;
+            }
 
-                right.sort();
-                right.reverse();
+            if ui.button("Reset").on_hover_text("Back to start").clicked() {
+                self.playing = false;
+                self.playback_step = 0;
+            }
 
-                left.sort();
-                left.reverse();
+            ui.add(
+                egui::Slider::new(&mut self.playback_speed, 0.25..=10.0)
+                    .logarithmic(true)
+                    .suffix(" steps/s")
+                    .text("Speed"),
+            );
+        });
 
-                output.append(&mut left);
-                if !sorted_vec.contains(&0) {
-                    output.push(0);
+        let shown = &schedule.steps[..self.playback_step];
+        let mut remaining = self.sequence.clone();
+        for step in shown.iter().filter(|step| step.services_request()) {
+            if let Some(pos) = remaining.iter().position(|&cylinder| cylinder == step.to) {
+                remaining.remove(pos);
+            }
+        }
+        let head = shown.last().map_or(self.arm_position_int, |step| step.to);
+        let moved: u32 = shown
+            .iter()
+            .map(|step| step.counted_distance(self.count_return_jump))
+            .sum();
+
+        egui::Grid::new("playback_grid")
+            .num_columns(2)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Step");
+                ui.label(format!("{} / {step_count}", self.playback_step));
+                ui.end_row();
+
+                ui.label("Head At");
+                ui.label(head.to_string());
+                ui.end_row();
+
+                ui.label("Seek So Far");
+                ui.label(moved.to_string());
+                ui.end_row();
+
+                ui.label("Remaining Queue");
+                if remaining.is_empty() {
+                    ui.label("Empty");
+                } else {
+                    let remaining: Vec<String> = remaining.iter().map(u32::to_string).collect();
+                    ui.label(remaining.join(", "));
                 }
-                if !sorted_vec.contains(&cylinder_count) {
-                    output.push(cylinder_count);
+                ui.end_row();
+            });
+    }
+
+    fn metrics_view(&self, ui: &mut egui::Ui) {
+        let unit = if self.simulate { "ms" } else { "steps" };
+        let runs: Vec<(Algorithm, Rc<Run>)> = Algorithm::ALL
+            .into_iter()
+            .map(|algorithm| (algorithm, self.run(algorithm)))
+            .collect();
+
+        ui.label(format!(
+            "Waiting time in {unit}. A request is starved when it waits at least {}x the mean.",
+            metrics::STARVATION_FACTOR
+        ));
+        egui::Grid::new("metrics_grid")
+            .num_columns(6)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["Algorithm", "Mean", "Max", "Std Dev", "95th Pct", "Starved"] {
+                    ui.strong(header);
                 }
-                output.append(&mut right);
-            }
+                ui.end_row();
+
+                for (algorithm, run) in &runs {
+                    let served: Vec<f64> = run.waits.iter().flatten().copied().collect();
+                    let stats = WaitStats::from_waits(&served);
+                    ui.colored_label(
+                        algorithm_color(*algorithm),
+                        self.scheduler(*algorithm).name(),
+                    );
+                    ui.label(format!("{:.2}", stats.mean));
+                    ui.label(format!("{:.2}", stats.max));
+                    ui.label(format!("{:.2}", stats.std_dev));
+                    ui.label(format!("{:.2}", stats.p95));
+                    if stats.starved > 0 {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("⚠ {}", stats.starved),
+                        );
+                    } else {
+                        ui.label("0");
+                    }
+                    ui.end_row();
+                }
+            });
 
-            Direction::Right => {
-                let mut right: Vec<u32> = Vec::new();
-                let mut left: Vec<u32> = Vec::new();
+        ui.separator();
+        ui.strong(format!("Waiting Time per Request ({unit})"));
+        egui::ScrollArea::both()
+            .id_source("wait_table")
+            .max_height(ui.available_height() - 40.0)
+            .show(ui, |ui| {
+                egui::Grid::new("wait_grid")
+                    .num_columns(runs.len() + 2)
+                    .spacing([24.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Request");
+                        ui.strong("Cylinder");
+                        for (algorithm, _) in &runs {
+                            ui.colored_label(algorithm_color(*algorithm), format!("{algorithm:?}"));
+                        }
+                        ui.end_row();
 
-                for &val in sorted_vec.iter() {
-                    if output.is_empty() {
-                        if val < head_value {
-                            left.push(val);
-                        } else {
-                            right.push(val);
+                        for (i, cylinder) in self.sequence.iter().enumerate() {
+                            ui.label((i + 1).to_string());
+                            ui.label(cylinder.to_string());
+                            for (_, run) in &runs {
+                                match run.waits.get(i).copied().flatten() {
+                                    Some(wait) => ui.label(format!("{wait:.2}")),
+                                    None => ui.label("-"),
+                                };
+                            }
+                            ui.end_row();
                         }
-                    }
+                    });
+            });
+    }
+
+    fn experiment_view(&mut self, ui: &mut egui::Ui) {
+        ui.label(format!(
+            "Runs every algorithm over workloads from the generator settings \
+             ({}, {} requests), one per seed starting at {}.",
+            self.workload.distribution.name(),
+            self.workload.count,
+            self.workload.seed
+        ));
+        ui.horizontal_wrapped(|ui| {
+            ui.label("workloads");
+            ui.add(egui::DragValue::new(&mut self.experiment_runs).clamp_range(1..=1000));
+            if ui.button("Run").clicked() {
+                let setup = Setup {
+                    head: self.arm_position_int,
+                    direction: self.direction,
+                    geometry: self.geometry(),
+                    tuning: self.tuning(),
+                    count_return_jump: self.count_return_jump,
+                };
+                self.running_experiment =
+                    Some(Experiment::new(setup, self.workload, self.experiment_runs));
+                self.experiment.clear();
+                self.experiment_status = None;
+            }
+            if let Some(experiment) = &self.running_experiment {
+                let (done, runs) = experiment.progress();
+                ui.add(
+                    egui::ProgressBar::new(done as f32 / runs as f32)
+                        .desired_width(160.0)
+                        .text(format!("{done} of {runs} workloads")),
+                );
+                if ui.button("Cancel").clicked() {
+                    self.running_experiment = None;
                 }
+            }
+        });
+        if self.experiment.is_empty() {
+            return;
+        }
 
-                right.sort();
-                left.sort();
+        ui.horizontal_wrapped(|ui| {
+            if ui.button("Copy CSV").clicked() {
+                ui.output_mut(|output| {
+                    output.copied_text = experiment::to_csv(&self.experiment);
+                });
+                self.experiment_status = Some(Ok("copied to the clipboard".to_owned()));
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                ui.add(egui::TextEdit::singleline(&mut self.experiment_path).hint_text("csv file"));
+                if ui.button("Save CSV").clicked() {
+                    self.experiment_status = Some(
+                        std::fs::write(&self.experiment_path, experiment::to_csv(&self.experiment))
+                            .map(|()| format!("saved to {}", self.experiment_path))
+                            .map_err(|err| format!("cannot write {}: {err}", self.experiment_path)),
+                    );
+                }
+            }
+            match &self.experiment_status {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(err)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, err);
+                }
+                None => {}
+            }
+        });
+
+        let plot_height = ui.available_height() - 40.0;
+        ui.allocate_ui(egui::vec2(ui.available_width(), plot_height), |ui| {
+            ui.columns(2, |columns| {
+                plot_trials(
+                    &mut columns[0],
+                    "Total Seek (cylinders)",
+                    &self.experiment,
+                    |trial| trial.total_movement as f64,
+                );
+                plot_trials(
+                    &mut columns[1],
+                    "Mean Response Time (ms)",
+                    &self.experiment,
+                    |trial| trial.mean_response_ms,
+                );
+            });
+        });
+    }
 
-                output.append(&mut right);
-                if !sorted_vec.contains(&cylinder_count) {
-                    output.push(cylinder_count);
+    fn quiz_view(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("problem");
+            let seed = ui.add(egui::DragValue::new(&mut self.quiz_seed));
+            let next = ui.button("New Problem").clicked();
+            if next {
+                self.quiz_seed = self.quiz_seed.wrapping_add(1);
+            }
+            if seed.changed() || next {
+                self.quiz_order.clear();
+                self.quiz_total.clear();
+                self.quiz_grade = None;
+                self.quiz_error = None;
+            }
+        });
+        let problem = Problem::generate(self.quiz_seed);
+        ui.label(problem.statement(self.count_return_jump));
+        ui.separator();
+
+        egui::Grid::new("quiz_answer")
+            .num_columns(2)
+            .spacing([40.0, 4.0])
+            .show(ui, |ui| {
+                ui.label("Service Order");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.quiz_order)
+                        .hint_text("e.g. 65, 67, 37")
+                        .desired_width(300.0),
+                );
+                ui.end_row();
+                ui.label("Total Head Movement");
+                ui.add(egui::TextEdit::singleline(&mut self.quiz_total).hint_text("cylinders"));
+                ui.end_row();
+            });
+        ui.horizontal_wrapped(|ui| {
+            if ui.button("Check").clicked() {
+                let answer = parse_cylinders(&self.quiz_order).and_then(|order| {
+                    let total = self.quiz_total.trim();
+                    total
+                        .parse()
+                        .map(|total| (order, total))
+                        .map_err(|_| format!("`{total}` is not a head movement"))
+                });
+                match answer {
+                    Ok((order, total)) => {
+                        self.quiz_grade =
+                            Some(problem.grade(&order, total, self.count_return_jump));
+                        self.quiz_error = None;
+                    }
+                    Err(err) => {
+                        self.quiz_grade = None;
+                        self.quiz_error = Some(err);
+                    }
                 }
-                if !sorted_vec.contains(&0) {
-                    output.push(0);
+            }
+            if let Some(err) = &self.quiz_error {
+                ui.colored_label(ui.visuals().error_fg_color, err);
+            }
+        });
+        let Some(grade) = &self.quiz_grade else {
+            return;
+        };
+
+        let right = egui::Color32::from_rgb(44, 160, 44);
+        let wrong = ui.visuals().error_fg_color;
+        if grade.passed() {
+            ui.colored_label(right, "Correct!");
+        }
+        // The typed order, right up to the first mistake.
+        let order = parse_cylinders(&self.quiz_order).unwrap_or_default();
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Your order:");
+            for (i, cylinder) in order.iter().enumerate() {
+                match grade.first_mistake {
+                    Some(mistake) if i == mistake => {
+                        ui.colored_label(wrong, cylinder.to_string());
+                    }
+                    Some(mistake) if i > mistake => {
+                        ui.weak(cylinder.to_string());
+                    }
+                    _ => {
+                        ui.colored_label(right, cylinder.to_string());
+                    }
                 }
-                output.append(&mut left);
             }
+            if grade.first_mistake == Some(order.len()) {
+                ui.colored_label(wrong, "(missing requests)");
+            }
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Correct order:");
+            ui.label(
+                grade
+                    .expected_order
+                    .iter()
+                    .map(u32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Total head movement:");
+            if grade.total_correct {
+                ui.colored_label(right, grade.expected_total.to_string());
+            } else {
+                ui.colored_label(wrong, self.quiz_total.trim());
+                ui.label(format!("(correct: {})", grade.expected_total));
+            }
+        });
+        if let Some(step) = grade.first_wrong_step {
+            ui.label(format!("Your answer first differs at step {}.", step + 1));
         }
-        output
+        ui.separator();
+
+        let timed = time_schedule(
+            &grade.schedule,
+            &problem.requests(),
+            problem.geometry(),
+            &self.seek_model,
+        );
+        let plot_height = (ui.available_height() * 0.6).max(150.0);
+        ui.allocate_ui(egui::vec2(ui.available_width(), plot_height), |ui| {
+            plot_schedule(ui, problem.algorithm, &grade.schedule, usize::MAX);
+        });
+        step_table(
+            ui,
+            &timed,
+            grade.first_wrong_step.map_or(0, |step| step + 1),
+            self.count_return_jump,
+            false,
+        );
     }
 }
 
+/// Column layout, units and filters of a CSV trace.
+fn csv_settings(ui: &mut egui::Ui, import: &mut CsvImport) {
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Columns: time");
+        ui.add(egui::DragValue::new(&mut import.timestamp_column));
+        ui.label("disk");
+        ui.add(egui::DragValue::new(&mut import.disk_column));
+        ui.label("type");
+        ui.add(egui::DragValue::new(&mut import.type_column));
+        ui.label("offset");
+        ui.add(egui::DragValue::new(&mut import.offset_column));
+    });
+    ui.horizontal_wrapped(|ui| {
+        egui::ComboBox::from_label("Time Unit")
+            .selected_text(import.time_unit.label())
+            .show_ui(ui, |ui| {
+                for unit in TimeUnit::ALL {
+                    ui.selectable_value(&mut import.time_unit, unit, unit.label());
+                }
+            });
+        ui.add(
+            egui::DragValue::new(&mut import.sector_bytes)
+                .clamp_range(1..=65_536)
+                .suffix(" bytes/sector"),
+        );
+    });
+    ui.horizontal_wrapped(|ui| {
+        let mut by_disk = import.disk.is_some();
+        ui.checkbox(&mut by_disk, "Only Disk");
+        let mut disk = import.disk.unwrap_or(0);
+        if by_disk {
+            ui.add(egui::DragValue::new(&mut disk));
+        }
+        import.disk = by_disk.then_some(disk);
+
+        let mut windowed = import.window_ms.is_some();
+        ui.checkbox(&mut windowed, "Time Window");
+        let [mut from, mut to] = import.window_ms.unwrap_or([0.0, 1000.0]);
+        if windowed {
+            ui.add(
+                egui::DragValue::new(&mut from)
+                    .clamp_range(0.0..=to)
+                    .suffix(" ms"),
+            );
+            ui.label("to");
+            ui.add(
+                egui::DragValue::new(&mut to)
+                    .clamp_range(from..=f64::MAX)
+                    .suffix(" ms"),
+            );
+        }
+        import.window_ms = windowed.then_some([from, to]);
+    });
+}
+
+/// When the last of `steps` finished.
+fn finish_ms(steps: &[TimedStep]) -> f64 {
+    steps.last().map_or(0.0, |step| step.finish_ms)
+}
+
+/// Position of `algorithm` in [`Algorithm::ALL`].
+fn algorithm_index(algorithm: Algorithm) -> usize {
+    Algorithm::ALL
+        .iter()
+        .position(|&other| other == algorithm)
+        .unwrap_or(0)
+}
+
+fn algorithm_color(algorithm: Algorithm) -> egui::Color32 {
+    ALGORITHM_COLORS[algorithm_index(algorithm)]
+}
+
 impl eframe::App for TemplateApp {
     /// Called by the frame work to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
@@ -274,21 +1832,62 @@ impl eframe::App for TemplateApp {
         // Put your widgets into a `SidePanel`, `TopBottomPanel`, `CentralPanel`, `Window` or `Area`.
         // For inspiration and more examples, go to https://emilk.github.io/egui
 
+        self.open_dropped_scenarios(ctx);
+        self.tick_experiment(ctx);
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.open_uploaded_scenarios();
+            self.follow_hash_changes();
+        }
+
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             // The top panel is often a good place for a menu bar:
             egui::menu::bar(ui, |ui| {
                 // NOTE: no File->Quit on web pages!
                 let is_web = cfg!(target_arch = "wasm32");
-                if !is_web {
-                    ui.menu_button("File", |ui| {
+                ui.menu_button("File", |ui| {
+                    self.scenario_menu(ui);
+                    if !is_web {
+                        ui.separator();
                         if ui.button("Quit").clicked() {
                             ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                         }
-                    });
+                    }
+                });
+                ui.add_space(16.0);
+
+                egui::widgets::global_dark_light_mode_buttons(ui);
+
+                #[cfg(target_arch = "wasm32")]
+                {
                     ui.add_space(16.0);
+                    // Keep the page URL pointing at what is on screen.
+                    let fragment = self.link().to_fragment();
+                    if fragment != self.shared_fragment {
+                        crate::web::replace_hash(&fragment);
+                        self.shared_fragment = fragment;
+                    }
+                    if ui
+                        .button("Copy Link")
+                        .on_hover_text("Copy a link that opens this workload")
+                        .clicked()
+                    {
+                        if let Some(url) = crate::web::page_url() {
+                            ui.output_mut(|output| output.copied_text = url);
+                            self.scenario_status = Some(Ok("link copied".to_owned()));
+                        }
+                    }
                 }
 
-                egui::widgets::global_dark_light_mode_buttons(ui);
+                match &self.scenario_status {
+                    Some(Ok(message)) => {
+                        ui.label(message);
+                    }
+                    Some(Err(err)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, err);
+                    }
+                    None => {}
+                }
             });
         });
 
@@ -301,7 +1900,12 @@ impl eframe::App for TemplateApp {
                 .striped(true)
                 .show(ui, |ui| {
                     ui.label("Total Cylinder");
-                    ui.add(egui::Slider::new(&mut self.cylinder_count, 0..=1000));
+                    // Loaded scenarios and links may use any disk up to the limit.
+                    ui.add(
+                        egui::Slider::new(&mut self.cylinder_count, 0..=MAX_CYLINDERS - 1)
+                            .logarithmic(true)
+                            .smallest_positive(1.0),
+                    );
 
                     ui.end_row();
 
@@ -321,262 +1925,295 @@ impl eframe::App for TemplateApp {
                             ui.selectable_value(&mut self.direction, Direction::Left, "Left");
                             ui.selectable_value(&mut self.direction, Direction::Right, "Right");
                         });
+
+                    ui.end_row();
+
+                    egui::ComboBox::from_label("SSTF Tie Break")
+                        .selected_text(format!("{tie:?}", tie = self.tie_break))
+                        .show_ui(ui, |ui| {
+                            ui.style_mut().wrap = Some(false);
+                            ui.set_min_width(60.0);
+                            ui.selectable_value(
+                                &mut self.tie_break,
+                                TieBreak::LowerCylinder,
+                                "Lower Cylinder",
+                            );
+                            ui.selectable_value(
+                                &mut self.tie_break,
+                                TieBreak::DirectionOfTravel,
+                                "Direction Of Travel",
+                            );
+                            ui.selectable_value(
+                                &mut self.tie_break,
+                                TieBreak::FirstArrived,
+                                "First Arrived",
+                            );
+                        });
+
+                    ui.end_row();
+
+                    if self.open_panel == Panel::Single(Algorithm::Deadline) {
+                        ui.label("Deadline");
+                        self.deadline_settings(ui);
+                        ui.end_row();
+                    }
+
+                    ui.label("N-Step Batch Size");
+                    ui.add(egui::Slider::new(&mut self.batch_size, 1..=32))
+                        .on_hover_text("Requests N-Step SCAN freezes into each batch");
+
+                    ui.end_row();
+
+                    ui.checkbox(&mut self.count_return_jump, "Count Return Jump")
+                        .on_hover_text(
+                            "Include the C-SCAN / C-LOOK jump back to the far end in the seek total",
+                        );
+
+                    ui.end_row();
+
+                    ui.checkbox(&mut self.simulate, "Simulate Arrivals").on_hover_text(
+                        "Requests join the queue at their arrival time instead of all at once",
+                    );
+
+                    ui.end_row();
+
+                    self.seek_model_settings(ui);
+
+                    ui.end_row();
+
+                    ui.checkbox(&mut self.model_rotation, "Model Rotation")
+                        .on_hover_text(
+                            "Wait for each request's sector to spin under the head and read it",
+                        );
+                    if self.model_rotation {
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::DragValue::new(&mut self.platter.rpm)
+                                    .clamp_range(1..=Platter::MAX_RPM)
+                                    .speed(10)
+                                    .suffix(" rpm"),
+                            );
+                            ui.add(
+                                egui::DragValue::new(&mut self.platter.sectors_per_track)
+                                    .clamp_range(1..=Platter::MAX_SECTORS_PER_TRACK)
+                                    .suffix(" sectors/track"),
+                            );
+                        });
+                    }
                 });
 
             ui.separator();
 
             ui.heading("Sequence Configuration");
+            self.sequence_text_edit(ui);
+            egui::CollapsingHeader::new("Generate Workload")
+                .show(ui, |ui| self.workload_generator(ui));
+            egui::CollapsingHeader::new("Import Trace").show(ui, |ui| self.trace_import(ui));
             egui::Grid::new("sequence_setting_grid")
                 .num_columns(2)
                 .spacing([40.0, 4.0])
                 .striped(true)
                 .show(ui, |ui| {
-                    for item in &mut self.sequence {
+                    self.arrivals.resize(self.sequence.len(), 0.0);
+                    self.sectors.resize(self.sequence.len(), 0);
+                    self.ops.resize(self.sequence.len(), Op::Read);
+                    self.notes.resize(self.sequence.len(), String::new());
+                    let last_sector = self.platter.sectors_per_track.max(1) - 1;
+                    let show_ops = self.open_panel == Panel::Single(Algorithm::Deadline);
+                    for ((((item, arrival), sector), op), note) in self
+                        .sequence
+                        .iter_mut()
+                        .zip(&mut self.arrivals)
+                        .zip(&mut self.sectors)
+                        .zip(&mut self.ops)
+                        .zip(&mut self.notes)
+                    {
                         ui.add(egui::Slider::new(item, 0..=self.cylinder_count).text("Sequence"));
+                        if self.model_rotation {
+                            *sector = (*sector).min(last_sector);
+                            ui.add(
+                                egui::DragValue::new(sector)
+                                    .clamp_range(0..=last_sector)
+                                    .prefix("sector "),
+                            );
+                        }
+                        if show_ops {
+                            ui.horizontal(|ui| {
+                                ui.selectable_value(op, Op::Read, "Read");
+                                ui.selectable_value(op, Op::Write, "Write");
+                            });
+                        }
+                        if self.simulate {
+                            ui.add(
+                                egui::DragValue::new(arrival)
+                                    .clamp_range(0.0..=f64::MAX)
+                                    .speed(0.5)
+                                    .suffix(" ms"),
+                            )
+                            .on_hover_text("Arrival time");
+                        }
+                        ui.add(
+                            egui::TextEdit::singleline(note)
+                                .hint_text("note")
+                                .desired_width(120.0),
+                        );
                         ui.end_row();
                     }
 
-                    if &self.sequence.len() == &0 {
+                    if self.sequence.is_empty() {
                         ui.label("Empty Sequence");
                         ui.end_row();
                     }
 
                     if ui.button("Add Sequence").clicked() {
                         self.sequence.push(0);
+                        self.arrivals.push(0.0);
+                        self.sectors.push(0);
+                        self.ops.push(Op::Read);
+                        self.notes.push(String::new());
                     };
                     if ui.button("Remove Sequence").clicked() {
                         self.sequence.pop();
+                        self.arrivals.pop();
+                        self.sectors.pop();
+                        self.ops.pop();
+                        self.notes.pop();
                     };
                     ui.end_row();
                 });
 
             ui.separator();
-            ui.horizontal(|ui| {
-                ui.selectable_value(
-                    &mut self.open_panel,
-                    Panel::SSTF,
-                    "Shortest Seek Time First",
-                );
-                ui.selectable_value(&mut self.open_panel, Panel::SCAN, "Scan");
-                ui.selectable_value(&mut self.open_panel, Panel::CSCAN, "Circular Scan");
-                ui.selectable_value(&mut self.open_panel, Panel::CLOOK, "Circular Look");
+            ui.horizontal_wrapped(|ui| {
+                for algorithm in Algorithm::ALL {
+                    let name = self.scheduler(algorithm).name();
+                    ui.selectable_value(&mut self.open_panel, Panel::Single(algorithm), name);
+                }
+                ui.selectable_value(&mut self.open_panel, Panel::Compare, "Compare All");
+                ui.selectable_value(&mut self.open_panel, Panel::Metrics, "Fairness");
+                ui.selectable_value(&mut self.open_panel, Panel::Experiment, "Monte Carlo");
+                ui.selectable_value(&mut self.open_panel, Panel::Quiz, "Quiz");
             });
 
             match self.open_panel {
-                Panel::SSTF => {
-                    egui_plot::Plot::new("SSTF")
-                        .y_axis_width(2)
-                        .data_aspect(1.0)
-                        .legend(egui_plot::Legend::default())
-                        .show(ui, |plot_ui| {
-                            let new_seq = TemplateApp::ordered_by_closeness(
-                                &self.sequence,
-                                self.arm_position_int,
+                Panel::Single(algorithm) => {
+                    let run = self.run(algorithm);
+                    let (schedule, timed) = (&run.schedule, &run.timed);
+                    self.tick_playback(ctx, schedule.steps.len());
+                    self.playback_controls(ui, schedule);
+                    self.figure_export(ui, algorithm, schedule);
+                    self.table_export(
+                        ui,
+                        |app| table::step_table(schedule, &app.seek_model, app.count_return_jump),
+                        &crate::scenario::key(algorithm),
+                    );
+
+                    // Leave room below the plot for the step table.
+                    let plot_height = (ui.available_height() * 0.6).max(150.0);
+                    ui.allocate_ui(egui::vec2(ui.available_width(), plot_height), |ui| {
+                        match self.geometry().platter {
+                            Some(platter) => ui.columns(2, |columns| {
+                                plot_schedule(
+                                    &mut columns[0],
+                                    algorithm,
+                                    schedule,
+                                    self.playback_step,
+                                );
+                                plot_rotation(
+                                    &mut columns[1],
+                                    algorithm,
+                                    platter,
+                                    timed,
+                                    self.playback_step,
+                                );
+                            }),
+                            None => plot_schedule(ui, algorithm, schedule, self.playback_step),
+                        }
+                    });
+                    ui.separator();
+
+                    if !self.simulate {
+                        self.detail_tab = DetailTab::Steps;
+                    }
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(&mut self.detail_tab, DetailTab::Steps, "Steps");
+                        if self.simulate {
+                            ui.selectable_value(
+                                &mut self.detail_tab,
+                                DetailTab::Requests,
+                                "Requests",
                             );
-                            for (i, el) in new_seq.iter().enumerate() {
-                                if i == 0 {
-                                    plot_ui.line(Line::new(PlotPoints::new(vec![
-                                        [self.arm_position_int as f64, 0.0],
-                                        [new_seq[i].to_owned() as f64, -5.0],
-                                    ])));
-                                    plot_ui.points(
-                                        egui_plot::Points::new(vec![[
-                                            new_seq[i].to_owned() as f64,
-                                            -5.0,
-                                        ]])
-                                        .shape(TemplateApp::arrow_direction(
-                                            self.arm_position_int as f64,
-                                            el.to_owned() as f64,
-                                        ))
-                                        .color(egui::Color32::BLUE)
-                                        .radius(8.0),
-                                    );
-                                } else {
-                                    let prev_y = -5.0 * i as f64;
-                                    plot_ui.line(Line::new(PlotPoints::new(vec![
-                                        [new_seq[i - 1].to_owned() as f64, -5.0 * i as f64],
-                                        [el.to_owned() as f64, prev_y - 5.0],
-                                    ])));
-
-                                    plot_ui.points(
-                                        egui_plot::Points::new(vec![[
-                                            new_seq[i].to_owned() as f64,
-                                            prev_y - 5.0,
-                                        ]])
-                                        .shape(TemplateApp::arrow_direction(
-                                            new_seq[i - 1] as f64,
-                                            el.to_owned() as f64,
-                                        ))
-                                        .color(egui::Color32::BLUE)
-                                        .radius(8.0),
-                                    );
-                                }
-                            }
-                        });
-                }
-                Panel::SCAN => {
-                    egui_plot::Plot::new("SCAN")
-                        .y_axis_width(2)
-                        .data_aspect(1.0)
-                        .legend(egui_plot::Legend::default())
-                        .show(ui, |plot_ui| {
-                            let new_seq = TemplateApp::scan(
-                                &self.sequence,
-                                self.arm_position_int,
-                                self.direction,
-                                self.cylinder_count,
+                            ui.selectable_value(&mut self.detail_tab, DetailTab::Queue, "Queue");
+                        }
+                    });
+                    match (self.detail_tab, &run.simulation) {
+                        (DetailTab::Requests, Some(simulation)) => request_table(ui, simulation),
+                        (DetailTab::Queue, Some(simulation)) => {
+                            queue_table(ui, simulation, &self.sequence);
+                        }
+                        _ => {
+                            step_table(
+                                ui,
+                                timed,
+                                self.playback_step,
+                                self.count_return_jump,
+                                self.model_rotation,
                             );
-
-                            for (i, el) in new_seq.iter().enumerate() {
-                                if i == 0 {
-                                    plot_ui.line(Line::new(PlotPoints::new(vec![
-                                        [self.arm_position_int as f64, 0.0],
-                                        [new_seq[i].to_owned() as f64, -5.0],
-                                    ])));
-                                    plot_ui.points(
-                                        egui_plot::Points::new(vec![[
-                                            new_seq[i].to_owned() as f64,
-                                            -5.0,
-                                        ]])
-                                        .shape(TemplateApp::arrow_direction(
-                                            self.arm_position_int as f64,
-                                            el.to_owned() as f64,
-                                        ))
-                                        .color(egui::Color32::BLUE)
-                                        .radius(8.0),
-                                    );
-                                } else {
-                                    let prev_y = -5.0 * i as f64;
-                                    plot_ui.line(Line::new(PlotPoints::new(vec![
-                                        [new_seq[i - 1].to_owned() as f64, -5.0 * i as f64],
-                                        [el.to_owned() as f64, prev_y - 5.0],
-                                    ])));
-
-                                    plot_ui.points(
-                                        egui_plot::Points::new(vec![[
-                                            new_seq[i].to_owned() as f64,
-                                            prev_y - 5.0,
-                                        ]])
-                                        .shape(TemplateApp::arrow_direction(
-                                            new_seq[i - 1] as f64,
-                                            el.to_owned() as f64,
-                                        ))
-                                        .color(egui::Color32::BLUE)
-                                        .radius(8.0),
-                                    );
-                                }
-                            }
-                        });
+                        }
+                    }
                 }
-                Panel::CSCAN => {
-                    egui_plot::Plot::new("CSCAN")
-                        .y_axis_width(2)
-                        .data_aspect(1.0)
-                        .legend(egui_plot::Legend::default())
-                        .clamp_grid(false)
-                        .show(ui, |plot_ui| {
-                            let new_seq = TemplateApp::cscan(
-                                &self.sequence,
-                                self.arm_position_int,
-                                self.direction,
-                                self.cylinder_count,
-                            );
-
-                            for (i, el) in new_seq.iter().enumerate() {
-                                if i == 0 {
-                                    plot_ui.line(Line::new(PlotPoints::new(vec![
-                                        [self.arm_position_int as f64, 0.0],
-                                        [new_seq[i].to_owned() as f64, -5.0],
-                                    ])));
-                                    plot_ui.points(
-                                        egui_plot::Points::new(vec![[
-                                            new_seq[i].to_owned() as f64,
-                                            -5.0,
-                                        ]])
-                                        .shape(TemplateApp::arrow_direction(
-                                            self.arm_position_int as f64,
-                                            el.to_owned() as f64,
-                                        ))
-                                        .color(egui::Color32::BLUE)
-                                        .radius(8.0),
-                                    );
-                                } else {
-                                    let prev_y = -5.0 * i as f64;
-                                    plot_ui.line(Line::new(PlotPoints::new(vec![
-                                        [new_seq[i - 1].to_owned() as f64, -5.0 * i as f64],
-                                        [el.to_owned() as f64, prev_y - 5.0],
-                                    ])));
-
-                                    plot_ui.points(
-                                        egui_plot::Points::new(vec![[
-                                            new_seq[i].to_owned() as f64,
-                                            prev_y - 5.0,
-                                        ]])
-                                        .shape(TemplateApp::arrow_direction(
-                                            new_seq[i - 1] as f64,
-                                            el.to_owned() as f64,
-                                        ))
-                                        .color(egui::Color32::BLUE)
-                                        .radius(8.0),
-                                    );
-                                }
+                Panel::Compare => {
+                    let rows = self.compare_rows();
+
+                    egui::Grid::new("compare_grid")
+                        .num_columns(6)
+                        .spacing([40.0, 4.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            self.compare_header(ui, CompareColumn::Algorithm, "Algorithm");
+                            self.compare_header(ui, CompareColumn::TotalSeek, "Total Seek");
+                            self.compare_header(ui, CompareColumn::AverageSeek, "Average Seek");
+                            self.compare_header(ui, CompareColumn::SeekTime, "Seek Time");
+                            if self.model_rotation {
+                                self.compare_header(
+                                    ui,
+                                    CompareColumn::AccessTime,
+                                    "With Rotation",
+                                );
                             }
-                        });
-                }
-                Panel::CLOOK => {
-                    egui_plot::Plot::new("CLOOK")
-                        .y_axis_width(2)
-                        .data_aspect(1.0)
-                        .legend(egui_plot::Legend::default())
-                        .clamp_grid(false)
-                        .show(ui, |plot_ui| {
-                            let new_seq = TemplateApp::clook(
-                                &self.sequence,
-                                self.arm_position_int,
-                                self.direction,
-                            );
-
-                            for (i, el) in new_seq.iter().enumerate() {
-                                if i == 0 {
-                                    plot_ui.line(Line::new(PlotPoints::new(vec![
-                                        [self.arm_position_int as f64, 0.0],
-                                        [new_seq[i].to_owned() as f64, -5.0],
-                                    ])));
-                                    plot_ui.points(
-                                        egui_plot::Points::new(vec![[
-                                            new_seq[i].to_owned() as f64,
-                                            -5.0,
-                                        ]])
-                                        .shape(TemplateApp::arrow_direction(
-                                            self.arm_position_int as f64,
-                                            el.to_owned() as f64,
-                                        ))
-                                        .color(egui::Color32::BLUE)
-                                        .radius(8.0),
-                                    );
-                                } else {
-                                    let prev_y = -5.0 * i as f64;
-                                    plot_ui.line(Line::new(PlotPoints::new(vec![
-                                        [new_seq[i - 1].to_owned() as f64, -5.0 * i as f64],
-                                        [el.to_owned() as f64, prev_y - 5.0],
-                                    ])));
-
-                                    plot_ui.points(
-                                        egui_plot::Points::new(vec![[
-                                            new_seq[i].to_owned() as f64,
-                                            prev_y - 5.0,
-                                        ]])
-                                        .shape(TemplateApp::arrow_direction(
-                                            new_seq[i - 1] as f64,
-                                            el.to_owned() as f64,
-                                        ))
-                                        .color(egui::Color32::BLUE)
-                                        .radius(8.0),
-                                    );
+                            self.compare_header(ui, CompareColumn::Reversals, "Reversals");
+                            ui.end_row();
+
+                            for row in &rows {
+                                ui.colored_label(algorithm_color(row.algorithm), row.name);
+                                ui.label(row.total.to_string());
+                                ui.label(format!("{:.2}", row.average));
+                                ui.label(format!("{:.2} ms", row.seek_ms));
+                                if self.model_rotation {
+                                    ui.label(format!("{:.2} ms", row.access_ms));
                                 }
+                                ui.label(row.reversals.to_string());
+                                ui.end_row();
                             }
                         });
+
+                    let summary = |app: &Self| {
+                        let schedules: Vec<(&str, &Schedule)> =
+                            rows.iter().map(|row| (row.name, &row.run.schedule)).collect();
+                        table::summary_table(
+                            &schedules,
+                            &app.sequence,
+                            &app.seek_model,
+                            app.count_return_jump,
+                        )
+                    };
+                    self.table_export(ui, summary, "summary");
+
+                    ui.separator();
+                    plot_comparison(ui, &rows);
                 }
+                Panel::Metrics => self.metrics_view(ui),
+                Panel::Experiment => self.experiment_view(ui),
+                Panel::Quiz => self.quiz_view(ui),
             }
 
             ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
@@ -585,91 +2222,391 @@ impl eframe::App for TemplateApp {
             });
         });
 
+        // The totals are those of the entered sequence, not of the quiz problem.
+        if self.open_panel == Panel::Quiz {
+            return;
+        }
         egui::TopBottomPanel::bottom("buttom_panel").show(ctx, |ui| {
             ui.horizontal(|ui| {
                 ui.heading("Seek Time");
                 match self.open_panel {
-                    Panel::SSTF => {
-                        let mut total_seq: i32 = 0;
-                        let new_seq = TemplateApp::ordered_by_closeness(
-                            &self.sequence,
-                            self.arm_position_int,
-                        );
-                        for (i, el) in new_seq.iter().enumerate() {
-                            if total_seq == 0 {
-                                println!(
-                                    "{}",
-                                    total_seq + (self.arm_position_int as i32 - *el as i32).abs()
-                                );
-                                total_seq =
-                                    total_seq + (self.arm_position_int as i32 - *el as i32).abs();
-                            } else {
-                                total_seq = total_seq + (new_seq[i - 1] as i32 - *el as i32).abs();
-                            }
-                        }
-                        ui.label(total_seq.to_string());
-                    }
-                    Panel::SCAN => {
-                        let mut total_seq: i32 = 0;
-                        let new_seq = TemplateApp::scan(
-                            &self.sequence,
-                            self.arm_position_int,
-                            self.direction,
-                            self.cylinder_count,
-                        );
-                        for (i, el) in new_seq.iter().enumerate() {
-                            if total_seq == 0 {
-                                println!(
-                                    "{}",
-                                    total_seq + (self.arm_position_int as i32 - *el as i32).abs()
-                                );
-                                total_seq =
-                                    total_seq + (self.arm_position_int as i32 - *el as i32).abs();
-                            } else {
-                                total_seq = total_seq + (new_seq[i - 1] as i32 - *el as i32).abs();
-                            }
+                    Panel::Single(algorithm) => {
+                        ui.label(self.seek_summary(algorithm));
+                    }
+                    Panel::Compare | Panel::Metrics | Panel::Experiment | Panel::Quiz => {
+                        for algorithm in Algorithm::ALL {
+                            ui.colored_label(
+                                algorithm_color(algorithm),
+                                format!(
+                                    "{}: {}",
+                                    self.scheduler(algorithm).name(),
+                                    self.seek_summary(algorithm)
+                                ),
+                            );
                         }
-                        ui.label(total_seq.to_string());
-                    }
-                    Panel::CSCAN => {
-                        let mut total_seq: i32 = 0;
-                        let new_seq = TemplateApp::cscan(
-                            &self.sequence,
-                            self.arm_position_int,
-                            self.direction,
-                            self.cylinder_count,
-                        );
-                        for (i, el) in new_seq.iter().enumerate() {
-                            if total_seq == 0 {
-                                total_seq =
-                                    total_seq + (self.arm_position_int as i32 - *el as i32).abs();
-                            } else {
-                                total_seq = total_seq + (new_seq[i - 1] as i32 - *el as i32).abs();
-                            }
+                    }
+                }
+            });
+        });
+    }
+}
+
+/// Draws the first `shown` steps of `schedule`, one step per row going downwards,
+/// highlighting the request serviced last and marking where new batches start.
+fn plot_schedule(ui: &mut egui::Ui, algorithm: Algorithm, schedule: &Schedule, shown: usize) {
+    egui_plot::Plot::new(format!("{algorithm:?}"))
+        .y_axis_width(2)
+        .data_aspect(1.0)
+        .legend(egui_plot::Legend::default())
+        .clamp_grid(false)
+        .show(ui, |plot_ui| {
+            let shown = shown.min(schedule.steps.len());
+            for (i, step) in schedule.steps[..shown].iter().enumerate() {
+                let prev_y = -5.0 * i as f64;
+                let current = i + 1 == shown;
+                let mut line = Line::new(PlotPoints::new(vec![
+                    [step.from as f64, prev_y],
+                    [step.to as f64, prev_y - 5.0],
+                ]));
+                if step.is_return_jump() {
+                    line = line.style(egui_plot::LineStyle::dashed_dense());
+                }
+                plot_ui.line(line);
+
+                // The first batch starts with the plot itself.
+                if i > 0 && schedule.batch_starts.contains(&i) {
+                    plot_ui.hline(
+                        egui_plot::HLine::new(prev_y)
+                            .color(egui::Color32::GRAY)
+                            .style(egui_plot::LineStyle::dashed_loose())
+                            .name("Batch Boundary"),
+                    );
+                }
+
+                let color = if current {
+                    egui::Color32::RED
+                } else if step.kind == StepKind::DeadlineExpiry {
+                    egui::Color32::from_rgb(255, 127, 14)
+                } else if step.services_request() {
+                    egui::Color32::BLUE
+                } else {
+                    egui::Color32::GRAY
+                };
+                plot_ui.points(
+                    egui_plot::Points::new(vec![[step.to as f64, prev_y - 5.0]])
+                        .shape(TemplateApp::arrow_direction(
+                            step.from as f64,
+                            step.to as f64,
+                        ))
+                        .color(color)
+                        .filled(step.services_request())
+                        .radius(if current { 11.0 } else { 8.0 }),
+                );
+            }
+        });
+}
+
+/// Line-by-line breakdown of a schedule, with the step at `current` highlighted.
+fn step_table(
+    ui: &mut egui::Ui,
+    steps: &[TimedStep],
+    current: usize,
+    count_return_jump: bool,
+    show_rotation: bool,
+) {
+    let mut headers = vec!["Step", "From", "To", "Distance", "Cumulative", "Seek Time"];
+    if show_rotation {
+        headers.extend(["Sector", "Rotation", "Transfer"]);
+    }
+    headers.push("Kind");
+
+    egui::ScrollArea::vertical()
+        .id_source("step_table")
+        .max_height(ui.available_height() - 40.0)
+        .show(ui, |ui| {
+            egui::Grid::new("step_grid")
+                .num_columns(headers.len())
+                .spacing([40.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for header in &headers {
+                        ui.strong(*header);
+                    }
+                    ui.end_row();
+
+                    let mut cumulative = 0;
+                    for (i, timed) in steps.iter().enumerate() {
+                        let step = timed.step;
+                        cumulative += step.counted_distance(count_return_jump);
+                        let counted = !step.is_return_jump() || count_return_jump;
+                        let seek_ms = if counted { timed.access.seek_ms } else { 0.0 };
+                        let mut cells = vec![
+                            (i + 1).to_string(),
+                            step.from.to_string(),
+                            step.to.to_string(),
+                            step.distance_label(count_return_jump),
+                            cumulative.to_string(),
+                            format!("{seek_ms:.2} ms"),
+                        ];
+                        if show_rotation {
+                            cells.push(timed.sector.map_or("-".to_owned(), |s| s.to_string()));
+                            cells.push(format!("{:.2} ms", timed.access.rotation_ms));
+                            cells.push(format!("{:.2} ms", timed.access.transfer_ms));
                         }
-                        ui.label(total_seq.to_string());
-                    }
-                    Panel::CLOOK => {
-                        let mut total_seq: i32 = 0;
-                        let new_seq = TemplateApp::clook(
-                            &self.sequence,
-                            self.arm_position_int,
-                            self.direction,
-                        );
-                        for (i, el) in new_seq.iter().enumerate() {
-                            if total_seq == 0 {
-                                total_seq =
-                                    total_seq + (self.arm_position_int as i32 - *el as i32).abs();
+                        cells.push(step.kind.step_label().to_owned());
+                        for cell in cells {
+                            if i + 1 == current {
+                                ui.strong(cell);
                             } else {
-                                total_seq = total_seq + (new_seq[i - 1] as i32 - *el as i32).abs();
+                                ui.label(cell);
                             }
                         }
-                        ui.label(total_seq.to_string());
+                        ui.end_row();
                     }
+                });
+        });
+}
+
+/// The angular position under the head over time for the first `shown` steps
+/// of a timed schedule, coloured by whether the head is seeking, waiting for
+/// the sector to come round, or reading it.
+fn plot_rotation(
+    ui: &mut egui::Ui,
+    algorithm: Algorithm,
+    platter: Platter,
+    steps: &[TimedStep],
+    shown: usize,
+) {
+    const PHASES: [(&str, egui::Color32); 3] = [
+        ("Seek", egui::Color32::from_rgb(31, 119, 180)),
+        ("Rotational Latency", egui::Color32::from_rgb(214, 39, 40)),
+        ("Transfer", egui::Color32::from_rgb(44, 160, 44)),
+    ];
+
+    egui_plot::Plot::new(format!("{algorithm:?} rotation"))
+        .legend(egui_plot::Legend::default())
+        .x_axis_label("Time (ms)")
+        .y_axis_label("Sector under head")
+        .include_y(0.0)
+        .include_y(platter.sectors_per_track as f64)
+        .show(ui, |plot_ui| {
+            let shown = shown.min(steps.len());
+            for timed in &steps[..shown] {
+                let access = timed.access;
+                let mut from = timed.start_ms;
+                for ((name, color), duration) in
+                    PHASES
+                        .into_iter()
+                        .zip([access.seek_ms, access.rotation_ms, access.transfer_ms])
+                {
+                    let to = from + duration;
+                    for segment in sawtooth(platter, from, to) {
+                        plot_ui.line(Line::new(PlotPoints::new(segment)).color(color).name(name));
+                    }
+                    from = to;
                 }
-            });
+                if let Some(sector) = timed.sector {
+                    plot_ui.points(
+                        egui_plot::Points::new(vec![[timed.finish_ms, sector as f64]])
+                            .color(egui::Color32::BLUE)
+                            .radius(4.0),
+                    );
+                }
+            }
         });
+}
+
+/// The angular position between `from_ms` and `to_ms`, split where it wraps
+/// back to sector 0.
+fn sawtooth(platter: Platter, from_ms: f64, to_ms: f64) -> Vec<Vec<[f64; 2]>> {
+    let revolution = platter.revolution_ms();
+    let track = platter.sectors_per_track.max(1) as f64;
+    let mut segments = Vec::new();
+    let mut start = from_ms;
+    while start < to_ms {
+        let wrap = ((start / revolution).floor() + 1.0) * revolution;
+        let end = wrap.min(to_ms);
+        let top = if end == wrap {
+            track
+        } else {
+            platter.position_at(end)
+        };
+        segments.push(vec![[start, platter.position_at(start)], [end, top]]);
+        start = end;
     }
+    segments
+}
+
+/// When each request arrived, started and finished in a simulation.
+fn request_table(ui: &mut egui::Ui, simulation: &Simulation) {
+    ui.label(format!(
+        "Mean response {:.2} ms, max response {:.2} ms, mean wait {:.2} ms, finished at {:.2} ms",
+        simulation.mean_response_ms(),
+        simulation.max_response_ms(),
+        simulation.mean_waiting_ms(),
+        simulation.makespan_ms(),
+    ));
+
+    egui::ScrollArea::vertical()
+        .id_source("request_table")
+        .max_height(ui.available_height() - 40.0)
+        .show(ui, |ui| {
+            egui::Grid::new("request_grid")
+                .num_columns(6)
+                .spacing([40.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for header in [
+                        "Request", "Cylinder", "Arrival", "Start", "Finish", "Response",
+                    ] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+
+                    let mut services = simulation.services.clone();
+                    services.sort_by_key(|service| service.request);
+                    for service in services {
+                        ui.label((service.request + 1).to_string());
+                        ui.label(service.cylinder.to_string());
+                        ui.label(format!("{:.2}", service.arrival_ms));
+                        ui.label(format!("{:.2}", service.start_ms));
+                        ui.label(format!("{:.2}", service.finish_ms));
+                        ui.label(format!("{:.2}", service.response_ms()));
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+/// How the queue contents changed over the course of a simulation.
+fn queue_table(ui: &mut egui::Ui, simulation: &Simulation, sequence: &[u32]) {
+    egui::ScrollArea::vertical()
+        .id_source("queue_table")
+        .max_height(ui.available_height() - 40.0)
+        .show(ui, |ui| {
+            egui::Grid::new("queue_grid")
+                .num_columns(3)
+                .spacing([40.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for header in ["Time", "Length", "Waiting Cylinders"] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+
+                    for snapshot in &simulation.timeline {
+                        let cylinders: Vec<String> = snapshot
+                            .pending
+                            .iter()
+                            .map(|&i| sequence[i].to_string())
+                            .collect();
+                        ui.label(format!("{:.2} ms", snapshot.time_ms));
+                        ui.label(snapshot.pending.len().to_string());
+                        ui.label(cylinders.join(", "));
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+/// One box per algorithm showing how `metric` spread over the trials, the
+/// whiskers reaching the best and worst trial.
+fn plot_trials(ui: &mut egui::Ui, title: &str, trials: &[Trial], metric: impl Fn(&Trial) -> f64) {
+    ui.strong(title);
+    egui_plot::Plot::new(title)
+        .legend(egui_plot::Legend::default())
+        .show_x(false)
+        .x_axis_formatter(|_, _, _| String::new())
+        .show(ui, |plot_ui| {
+            for (i, algorithm) in Algorithm::ALL.into_iter().enumerate() {
+                let values: Vec<f64> = trials
+                    .iter()
+                    .filter(|trial| trial.algorithm == algorithm)
+                    .map(&metric)
+                    .collect();
+                let Some(spread) = Spread::of(&values) else {
+                    continue;
+                };
+                let name = format!("{algorithm:?}");
+                let color = algorithm_color(algorithm);
+                let elem = egui_plot::BoxElem::new(
+                    i as f64,
+                    egui_plot::BoxSpread::new(
+                        spread.min,
+                        spread.q1,
+                        spread.median,
+                        spread.q3,
+                        spread.max,
+                    ),
+                )
+                .name(&name)
+                .box_width(0.6)
+                .whisker_width(0.3);
+                plot_ui.box_plot(egui_plot::BoxPlot::new(vec![elem]).color(color).name(&name));
+                plot_ui.points(
+                    egui_plot::Points::new(vec![[i as f64, spread.mean]])
+                        .shape(egui_plot::MarkerShape::Diamond)
+                        .radius(4.0)
+                        .color(color)
+                        .name(&name),
+                );
+            }
+        });
+}
+
+/// Overlays the head movement of every compared algorithm, one colour and
+/// legend entry each.
+fn plot_comparison(ui: &mut egui::Ui, rows: &[CompareRow]) {
+    egui_plot::Plot::new("Compare")
+        .y_axis_width(2)
+        .data_aspect(1.0)
+        .legend(egui_plot::Legend::default())
+        .clamp_grid(false)
+        .show(ui, |plot_ui| {
+            for row in rows {
+                let color = algorithm_color(row.algorithm);
+                let mut path = Vec::with_capacity(row.run.schedule.steps.len() + 1);
+                if let Some(first) = row.run.schedule.steps.first() {
+                    path.push([first.from as f64, 0.0]);
+                }
+                for (i, step) in row.run.schedule.steps.iter().enumerate() {
+                    let point = [step.to as f64, -5.0 * (i + 1) as f64];
+                    if step.is_return_jump() {
+                        // Break the solid path and draw the jump dashed.
+                        let start = path.last().copied().unwrap_or(point);
+                        plot_ui.line(
+                            Line::new(PlotPoints::new(std::mem::take(&mut path)))
+                                .color(color)
+                                .name(row.name),
+                        );
+                        plot_ui.line(
+                            Line::new(PlotPoints::new(vec![start, point]))
+                                .color(color)
+                                .style(egui_plot::LineStyle::dashed_dense())
+                                .name(row.name),
+                        );
+                    }
+                    path.push(point);
+                }
+
+                let points: Vec<[f64; 2]> = row
+                    .run
+                    .schedule
+                    .steps
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, step)| step.services_request())
+                    .map(|(i, step)| [step.to as f64, -5.0 * (i + 1) as f64])
+                    .collect();
+                plot_ui.line(Line::new(PlotPoints::new(path)).color(color).name(row.name));
+                plot_ui.points(
+                    egui_plot::Points::new(points)
+                        .color(color)
+                        .radius(3.0)
+                        .name(row.name),
+                );
+            }
+        });
 }
 
 fn powered_by_egui_and_eframe(ui: &mut egui::Ui) {
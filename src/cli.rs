@@ -0,0 +1,379 @@
+//! The headless `disk-peek schedule ...` command, for grading and scripting.
+
+use crate::figure;
+use crate::sched::{
+    parse_requests, Algorithm, Direction, Geometry, Request, Schedule, TieBreak, Tuning,
+};
+use crate::sim::SeekModel;
+use crate::table::{self, TableFormat};
+
+pub const USAGE: &str = "\
+Usage: disk-peek schedule --algo <ALGO> --cylinders <N> --head <CYL> [OPTIONS] <REQUESTS>...
+       disk-peek schedule --summary --format <TABLE> --cylinders <N> --head <CYL> <REQUESTS>...
+
+Prints the order in which the head services REQUESTS and the total head movement,
+or with --summary a table comparing every algorithm.
+REQUESTS are cylinder numbers separated by commas and/or spaces; prefix one
+with w to make it a write for the deadline scheduler.
+
+Options:
+  --algo <ALGO>        fcfs, sstf, scan, cscan, look, clook, sptf, nstep, fscan
+                       or deadline
+  --cylinders <N>      number of cylinders on the disk (cylinders 0..N-1)
+  --head <CYL>         starting cylinder of the head
+  --dir <DIR>          initial direction, left or right [default: right]
+  --tie-break <RULE>   SSTF tie break: lower, direction or first [default: lower]
+  --batch-size <N>     requests per N-Step SCAN batch [default: 4]
+  --format <FORMAT>    text, json, or a table of the steps as csv, markdown
+                       or latex [default: text]
+  --summary            compare every algorithm in a csv, markdown or latex table
+  --no-return-jump     leave the C-SCAN / C-LOOK return jump out of the total
+  --svg <FILE>         also draw the schedule to an SVG file
+  --png <FILE>         also draw the schedule to a PNG file
+  -h, --help           print this help
+";
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Format {
+    Text,
+    Json,
+    Table(TableFormat),
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => s.parse().map(Format::Table).map_err(|_| {
+                format!(
+                    "unknown format `{s}` (expected `text`, `json`, `csv`, `markdown` or `latex`)"
+                )
+            }),
+        }
+    }
+}
+
+/// Arguments of the `schedule` subcommand.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ScheduleArgs {
+    /// Only optional with `summary`, which runs every algorithm.
+    pub algorithm: Option<Algorithm>,
+    pub cylinders: u32,
+    pub head: u32,
+    pub direction: Direction,
+    pub tie_break: TieBreak,
+    pub batch_size: usize,
+    pub format: Format,
+    pub count_return_jump: bool,
+    pub summary: bool,
+    pub requests: Vec<Request>,
+    /// Where to draw the schedule as SVG.
+    pub svg: Option<String>,
+    /// Where to draw the schedule as PNG.
+    pub png: Option<String>,
+}
+
+impl ScheduleArgs {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut algorithm = None;
+        let mut cylinders = None;
+        let mut head = None;
+        let mut direction = Direction::Right;
+        let mut tie_break = TieBreak::LowerCylinder;
+        let mut batch_size = 4;
+        let mut format = Format::Text;
+        let mut count_return_jump = true;
+        let mut summary = false;
+        let mut requests = Vec::new();
+        let mut svg = None;
+        let mut png = None;
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            // Accept both `--flag value` and `--flag=value`.
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if arg.starts_with("--") => (flag, Some(value.to_owned())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| iter.next().cloned())
+                    .ok_or_else(|| format!("missing value for `{flag}`"))
+            };
+
+            match flag {
+                "--algo" => algorithm = Some(value()?.parse()?),
+                "--cylinders" => cylinders = Some(parse_number(&value()?, flag)?),
+                "--head" => head = Some(parse_number(&value()?, flag)?),
+                "--dir" => direction = value()?.parse()?,
+                "--tie-break" => tie_break = value()?.parse()?,
+                "--batch-size" => batch_size = parse_number(&value()?, flag)? as usize,
+                "--format" => format = value()?.parse()?,
+                "--no-return-jump" => count_return_jump = false,
+                "--summary" => summary = true,
+                "--svg" => svg = Some(value()?),
+                "--png" => png = Some(value()?),
+                flag if flag.starts_with("--") => return Err(format!("unknown option `{flag}`")),
+                _ => requests.extend(parse_requests(arg)?),
+            }
+        }
+
+        let args = Self {
+            algorithm,
+            cylinders: cylinders.ok_or("missing `--cylinders`")?,
+            head: head.ok_or("missing `--head`")?,
+            direction,
+            tie_break,
+            batch_size,
+            format,
+            count_return_jump,
+            summary,
+            requests,
+            svg,
+            png,
+        };
+
+        if args.cylinders == 0 {
+            return Err("`--cylinders` must be at least 1".to_owned());
+        }
+        if args.algorithm.is_none() && !args.summary {
+            return Err("missing `--algo`".to_owned());
+        }
+        if args.summary && !matches!(args.format, Format::Table(_)) {
+            return Err("`--summary` needs `--format csv`, `markdown` or `latex`".to_owned());
+        }
+        if args.batch_size == 0 {
+            return Err("`--batch-size` must be at least 1".to_owned());
+        }
+        let last = args.cylinders - 1;
+        if args.head > last {
+            return Err(format!(
+                "head {} is outside cylinders 0..={last}",
+                args.head
+            ));
+        }
+        if let Some(request) = args.requests.iter().find(|request| request.cylinder > last) {
+            return Err(format!(
+                "request {} is outside cylinders 0..={last}",
+                request.cylinder
+            ));
+        }
+        Ok(args)
+    }
+
+    pub fn geometry(&self) -> Geometry {
+        Geometry::cylinders(self.cylinders - 1)
+    }
+}
+
+fn parse_number(s: &str, what: &str) -> Result<u32, String> {
+    s.trim()
+        .parse()
+        .map_err(|_| format!("invalid number `{s}` for {what}"))
+}
+
+/// Runs the `schedule` subcommand and returns what should be printed to stdout.
+pub fn run(args: &[String]) -> Result<String, String> {
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        return Ok(USAGE.to_owned());
+    }
+
+    let args = ScheduleArgs::parse(args)?;
+    let tuning = Tuning {
+        tie_break: args.tie_break,
+        batch_size: args.batch_size,
+        ..Default::default()
+    };
+    let queue: Vec<u32> = args
+        .requests
+        .iter()
+        .map(|request| request.cylinder)
+        .collect();
+
+    if let (true, Format::Table(format)) = (args.summary, args.format) {
+        let schedules: Vec<(&str, Schedule)> = Algorithm::ALL
+            .into_iter()
+            .map(|algorithm| {
+                let scheduler = algorithm.scheduler(&tuning);
+                let schedule =
+                    scheduler.schedule(&args.requests, args.head, args.direction, args.geometry());
+                (scheduler.name(), schedule)
+            })
+            .collect();
+        let schedules: Vec<(&str, &Schedule)> = schedules
+            .iter()
+            .map(|(name, schedule)| (*name, schedule))
+            .collect();
+        let summary = table::summary_table(
+            &schedules,
+            &queue,
+            &SeekModel::default(),
+            args.count_return_jump,
+        );
+        return Ok(summary.render(format));
+    }
+
+    let algorithm = args.algorithm.ok_or("missing `--algo`")?;
+    let scheduler = algorithm.scheduler(&tuning);
+    let schedule = scheduler.schedule(&args.requests, args.head, args.direction, args.geometry());
+
+    if args.svg.is_some() || args.png.is_some() {
+        let figure = figure::schedule_figure(
+            scheduler.name(),
+            &schedule,
+            args.cylinders - 1,
+            args.count_return_jump,
+            [31, 119, 180],
+        );
+        if let Some(path) = &args.svg {
+            std::fs::write(path, figure.to_svg())
+                .map_err(|err| format!("cannot write {path}: {err}"))?;
+        }
+        if let Some(path) = &args.png {
+            std::fs::write(path, figure.to_png()?)
+                .map_err(|err| format!("cannot write {path}: {err}"))?;
+        }
+    }
+
+    Ok(match args.format {
+        Format::Text => render_text(scheduler.name(), &args, &schedule),
+        Format::Json => render_json(scheduler.name(), &args, &schedule),
+        Format::Table(format) => {
+            table::step_table(&schedule, &SeekModel::default(), args.count_return_jump)
+                .render(format)
+        }
+    })
+}
+
+fn render_text(name: &str, args: &ScheduleArgs, schedule: &Schedule) -> String {
+    let mut path = args.head.to_string();
+    for (i, step) in schedule.steps.iter().enumerate() {
+        if i > 0 && schedule.batch_starts.contains(&i) {
+            path.push_str(" |");
+        }
+        let arrow = if step.is_return_jump() {
+            " ~> "
+        } else {
+            " -> "
+        };
+        path.push_str(arrow);
+        path.push_str(&step.to.to_string());
+    }
+
+    let mut text = format!(
+        "Algorithm: {name}\nService order: {path}\nTotal head movement: {}\n",
+        schedule.total_movement(args.count_return_jump)
+    );
+    if schedule.batch_starts.len() > 1 {
+        text.push_str("(| marks the start of a new batch)\n");
+    }
+    if schedule.steps.iter().any(|step| step.is_return_jump()) {
+        let counted = if args.count_return_jump {
+            "counted"
+        } else {
+            "not counted"
+        };
+        text.push_str(&format!(
+            "(~> is the return jump, {counted} in the total)\n"
+        ));
+    }
+    text
+}
+
+/// The `--format json` report.
+#[derive(serde::Serialize)]
+struct Report<'a> {
+    algorithm: &'a str,
+    cylinders: u32,
+    head: u32,
+    direction: String,
+    order: &'a [u32],
+    steps: Vec<StepReport>,
+    batch_starts: &'a [usize],
+    count_return_jump: bool,
+    total_movement: u32,
+}
+
+#[derive(serde::Serialize)]
+struct StepReport {
+    from: u32,
+    to: u32,
+    distance: u32,
+    kind: &'static str,
+    services_request: bool,
+}
+
+fn render_json(name: &str, args: &ScheduleArgs, schedule: &Schedule) -> String {
+    let steps = schedule
+        .steps
+        .iter()
+        .map(|step| StepReport {
+            from: step.from,
+            to: step.to,
+            distance: step.distance,
+            kind: step.kind.label(),
+            services_request: step.services_request(),
+        })
+        .collect();
+    let report = Report {
+        algorithm: name,
+        cylinders: args.cylinders,
+        head: args.head,
+        direction: format!("{:?}", args.direction),
+        order: &schedule.order,
+        steps,
+        batch_starts: &schedule.batch_starts,
+        count_return_jump: args.count_return_jump,
+        total_movement: schedule.total_movement(args.count_return_jump),
+    };
+    let json = serde_json::to_string(&report).expect("report serializes");
+    format!("{json}\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_with(args: &str) -> Result<String, String> {
+        let args: Vec<String> = args.split_whitespace().map(str::to_owned).collect();
+        run(&args)
+    }
+
+    #[test]
+    fn prints_the_service_order_and_total() {
+        let text = run_with("--algo cscan --cylinders 200 --head 53 98,183,37,122,14,124,65,67");
+        assert_eq!(
+            text.unwrap(),
+            "Algorithm: Circular Scan\n\
+             Service order: 53 -> 65 -> 67 -> 98 -> 122 -> 124 -> 183 -> 199 ~> 0 -> 14 -> 37\n\
+             Total head movement: 382\n\
+             (~> is the return jump, counted in the total)\n"
+        );
+        let text = run_with("--algo cscan --cylinders 200 --head 53 --dir right 60,70").unwrap();
+        assert!(text.contains("Service order: 53 -> 60 -> 70\nTotal head movement: 17\n"));
+    }
+
+    #[test]
+    fn json_report() {
+        let json = run_with("--algo scan --cylinders=200 --head 53 --format json 98 183 37");
+        assert_eq!(
+            json.unwrap(),
+            r#"{"algorithm":"Scan","cylinders":200,"head":53,"direction":"Right","order":[98,183,199,37],"steps":[{"from":53,"to":98,"distance":45,"kind":"Request","services_request":true},{"from":98,"to":183,"distance":85,"kind":"Request","services_request":true},{"from":183,"to":199,"distance":16,"kind":"Edge Sweep","services_request":false},{"from":199,"to":37,"distance":162,"kind":"Request","services_request":true}],"batch_starts":[],"count_return_jump":true,"total_movement":308}"#
+                .to_owned()
+                + "\n"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(run_with("--cylinders 200 --head 53 1").is_err());
+        assert!(run_with("--algo scan --cylinders 200 --head 200 1").is_err());
+        assert!(run_with("--algo scan --cylinders 200 --head 5 200").is_err());
+        assert!(run_with("--algo elevator --cylinders 200 --head 5 1").is_err());
+        assert!(run_with("--algo scan --cylinders 200 --head 5 --summary 1").is_err());
+    }
+}
@@ -0,0 +1,224 @@
+//! Monte Carlo comparison of the algorithms over many generated workloads.
+
+use crate::sched::{Algorithm, Direction, DiskScheduler, Geometry, Tuning};
+use crate::sim::Simulation;
+use crate::workload::{self, WorkloadSpec};
+
+/// The disk and scheduler settings every trial shares.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Setup {
+    pub head: u32,
+    pub direction: Direction,
+    pub geometry: Geometry,
+    pub tuning: Tuning,
+    /// Whether the C-SCAN / C-LOOK return jump counts toward head movement.
+    pub count_return_jump: bool,
+}
+
+/// How one algorithm did on one generated workload.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Trial {
+    pub algorithm: Algorithm,
+    /// Seed the workload was generated from.
+    pub seed: u64,
+    pub total_movement: u32,
+    pub mean_response_ms: f64,
+}
+
+/// Runs every algorithm over `runs` workloads generated from `spec`, the
+/// first with its seed and each following one with the next seed.
+///
+/// Every trial is simulated, so workloads without arrival times behave as if
+/// the whole queue was there at time 0.
+pub fn run(setup: &Setup, spec: &WorkloadSpec, runs: usize) -> Vec<Trial> {
+    let mut experiment = Experiment::new(setup.clone(), *spec, runs);
+    let mut trials = Vec::with_capacity(runs * Algorithm::ALL.len());
+    while !experiment.is_done() {
+        trials.extend(experiment.advance(usize::MAX));
+    }
+    trials
+}
+
+/// The same comparison as [`run`], worked through a few workloads at a time
+/// so the app can keep drawing frames and show how far it got.
+pub struct Experiment {
+    setup: Setup,
+    spec: WorkloadSpec,
+    runs: usize,
+    /// Workloads run so far.
+    done: usize,
+    schedulers: Vec<(Algorithm, Box<dyn DiskScheduler>)>,
+}
+
+impl Experiment {
+    pub fn new(setup: Setup, spec: WorkloadSpec, runs: usize) -> Self {
+        let schedulers = Algorithm::ALL
+            .into_iter()
+            .map(|algorithm| (algorithm, algorithm.scheduler(&setup.tuning)))
+            .collect();
+        Self {
+            setup,
+            spec,
+            runs,
+            done: 0,
+            schedulers,
+        }
+    }
+
+    /// Runs the next workloads, as many as fit in `requests` generated
+    /// requests but at least one, and returns their trials.
+    pub fn advance(&mut self, requests: usize) -> Vec<Trial> {
+        let workloads = (requests / self.spec.count.max(1)).max(1);
+        let mut trials = Vec::new();
+        while self.done < self.runs && trials.len() < workloads * self.schedulers.len() {
+            let spec = WorkloadSpec {
+                seed: self.spec.seed.wrapping_add(self.done as u64),
+                ..self.spec
+            };
+            let queue = workload::generate(&spec, self.setup.geometry);
+            for (algorithm, scheduler) in &self.schedulers {
+                let simulation = Simulation::run(
+                    scheduler.as_ref(),
+                    &queue,
+                    self.setup.head,
+                    self.setup.direction,
+                    self.setup.geometry,
+                    &self.setup.tuning.seek,
+                );
+                trials.push(Trial {
+                    algorithm: *algorithm,
+                    seed: spec.seed,
+                    total_movement: simulation
+                        .schedule
+                        .total_movement(self.setup.count_return_jump),
+                    mean_response_ms: simulation.mean_response_ms(),
+                });
+            }
+            self.done += 1;
+        }
+        trials
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done >= self.runs
+    }
+
+    /// Workloads run so far and in total.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.done, self.runs)
+    }
+}
+
+/// Five-number summary of a sample, plus its mean.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Spread {
+    pub min: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl Spread {
+    /// Quartiles are linearly interpolated between the closest ranks.
+    pub fn of(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let quantile = |q: f64| {
+            let rank = q * (sorted.len() - 1) as f64;
+            let below = sorted[rank.floor() as usize];
+            let above = sorted[rank.ceil() as usize];
+            below + (above - below) * rank.fract()
+        };
+        Some(Self {
+            min: sorted[0],
+            q1: quantile(0.25),
+            median: quantile(0.5),
+            q3: quantile(0.75),
+            max: sorted[sorted.len() - 1],
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+        })
+    }
+}
+
+/// The raw trials as CSV, one line per algorithm and workload.
+pub fn to_csv(trials: &[Trial]) -> String {
+    let mut csv = String::from("algorithm,seed,total_movement,mean_response_ms\n");
+    for trial in trials {
+        csv.push_str(&format!(
+            "{:?},{},{},{:.3}\n",
+            trial.algorithm, trial.seed, trial.total_movement, trial.mean_response_ms
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spread() {
+        let spread = Spread::of(&[4.0, 1.0, 3.0, 2.0]).unwrap();
+        assert_eq!(
+            spread,
+            Spread {
+                min: 1.0,
+                q1: 1.75,
+                median: 2.5,
+                q3: 3.25,
+                max: 4.0,
+                mean: 2.5,
+            }
+        );
+        assert_eq!(Spread::of(&[7.0]).unwrap().q1, 7.0);
+        assert_eq!(Spread::of(&[]), None);
+    }
+
+    #[test]
+    fn runs_every_algorithm_on_every_workload() {
+        let setup = Setup {
+            head: 53,
+            direction: Direction::Left,
+            geometry: Geometry::cylinders(199),
+            tuning: Tuning::default(),
+            count_return_jump: true,
+        };
+        let spec = WorkloadSpec {
+            seed: 7,
+            ..Default::default()
+        };
+        let trials = run(&setup, &spec, 3);
+        assert_eq!(trials.len(), 3 * Algorithm::ALL.len());
+        assert_eq!(trials[0].seed, 7);
+        assert_eq!(trials[trials.len() - 1].seed, 9);
+        assert_eq!(run(&setup, &spec, 3), trials);
+
+        // Worked through one workload at a time, the trials are the same.
+        let mut experiment = Experiment::new(setup, spec, 3);
+        let mut stepped = Vec::new();
+        while !experiment.is_done() {
+            stepped.extend(experiment.advance(1));
+            assert_eq!(
+                stepped.len(),
+                experiment.progress().0 * Algorithm::ALL.len()
+            );
+        }
+        assert_eq!(stepped, trials);
+
+        let csv = to_csv(&trials[..1]);
+        let fcfs = &trials[0];
+        assert_eq!(fcfs.algorithm, Algorithm::Fcfs);
+        assert_eq!(
+            csv,
+            format!(
+                "algorithm,seed,total_movement,mean_response_ms\nFcfs,7,{},{:.3}\n",
+                fcfs.total_movement, fcfs.mean_response_ms
+            )
+        );
+    }
+}
@@ -0,0 +1,441 @@
+//! Standalone pictures of a schedule for slides and exams, drawn from the
+//! schedule itself rather than grabbed from the screen so they can also be
+//! made from the command line.
+//!
+//! The schedule is laid out once as a list of shapes, which are then written
+//! out as SVG or, on native, rendered from that SVG to PNG.
+
+use crate::sched::{Schedule, StepKind};
+
+pub type Rgb = [u8; 3];
+
+const WHITE: Rgb = [255, 255, 255];
+const BLACK: Rgb = [0, 0, 0];
+const GRID: Rgb = [225, 225, 225];
+const GRAY: Rgb = [128, 128, 128];
+const BLUE: Rgb = [0, 0, 255];
+const ORANGE: Rgb = [255, 127, 14];
+
+const WIDTH: f32 = 800.0;
+const LEFT: f32 = 84.0;
+/// Right edge of the step numbers, leaving room for labels of markers at cylinder 0.
+const STEP_LABELS: f32 = 48.0;
+const RIGHT: f32 = 40.0;
+const TOP: f32 = 96.0;
+const BOTTOM: f32 = 24.0;
+const ROW: f32 = 24.0;
+/// On and off lengths of dashed lines.
+const DASH: [f32; 2] = [6.0, 4.0];
+/// Steps drawn at most. Longer schedules, such as imported traces, are cut
+/// off so the picture and the PNG made from it stay a sensible size.
+pub const MAX_ROWS: usize = 250;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum Shape {
+    Line {
+        from: [f32; 2],
+        to: [f32; 2],
+        color: Rgb,
+        width: f32,
+        dashed: bool,
+    },
+    /// A triangle outlined in `color`, and filled with it when `fill` is set.
+    Triangle {
+        points: [[f32; 2]; 3],
+        color: Rgb,
+        fill: bool,
+    },
+    Circle {
+        center: [f32; 2],
+        radius: f32,
+        color: Rgb,
+    },
+    /// `at` is on the baseline.
+    Text {
+        at: [f32; 2],
+        text: String,
+        size: f32,
+        color: Rgb,
+        anchor: Anchor,
+        bold: bool,
+    },
+}
+
+/// A picture in pixels, `y` growing downwards.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Figure {
+    pub width: f32,
+    pub height: f32,
+    pub shapes: Vec<Shape>,
+}
+
+/// Lays out `schedule` the way the schedule plot shows it: one step per row
+/// going down, cylinders across, with the algorithm name and total head
+/// movement above. Only the first [`MAX_ROWS`] steps are drawn.
+pub fn schedule_figure(
+    name: &str,
+    schedule: &Schedule,
+    max_cylinder: u32,
+    count_return_jump: bool,
+    color: Rgb,
+) -> Figure {
+    let steps = &schedule.steps[..schedule.steps.len().min(MAX_ROWS)];
+    let rows = steps.len().max(1);
+    let plot_width = WIDTH - LEFT - RIGHT;
+    let plot_height = rows as f32 * ROW;
+    let x = |cylinder: u32| LEFT + cylinder as f32 / max_cylinder.max(1) as f32 * plot_width;
+    let y = |row: usize| TOP + row as f32 * ROW;
+
+    let mut total = format!(
+        "Total head movement: {} cylinders",
+        schedule.total_movement(count_return_jump)
+    );
+    if steps.len() < schedule.steps.len() {
+        total.push_str(&format!(
+            " (first {} of {} steps shown)",
+            steps.len(),
+            schedule.steps.len()
+        ));
+    }
+    let mut shapes = vec![
+        text([STEP_LABELS - 24.0, 28.0], name, 20.0, Anchor::Start, true),
+        text(
+            [STEP_LABELS - 24.0, 50.0],
+            &total,
+            14.0,
+            Anchor::Start,
+            false,
+        ),
+        text(
+            [LEFT + plot_width / 2.0, 66.0],
+            "Cylinder",
+            12.0,
+            Anchor::Middle,
+            false,
+        ),
+        text([STEP_LABELS, TOP - 8.0], "Step", 12.0, Anchor::End, false),
+    ];
+
+    let step = tick_step(max_cylinder);
+    for cylinder in (0..=max_cylinder).step_by(step as usize) {
+        let at = x(cylinder);
+        shapes.push(line([at, TOP], [at, TOP + plot_height], GRID, 1.0));
+        shapes.push(text(
+            [at, TOP - 8.0],
+            &cylinder.to_string(),
+            11.0,
+            Anchor::Middle,
+            false,
+        ));
+    }
+    let every = (rows + 29) / 30;
+    for row in (0..=rows).step_by(every) {
+        shapes.push(text(
+            [STEP_LABELS, y(row) + 4.0],
+            &row.to_string(),
+            11.0,
+            Anchor::End,
+            false,
+        ));
+    }
+    // Frame of the plot area.
+    let corners = [
+        [LEFT, TOP],
+        [LEFT + plot_width, TOP],
+        [LEFT + plot_width, TOP + plot_height],
+        [LEFT, TOP + plot_height],
+    ];
+    for i in 0..4 {
+        shapes.push(line(corners[i], corners[(i + 1) % 4], GRAY, 1.0));
+    }
+
+    for &start in schedule
+        .batch_starts
+        .iter()
+        .filter(|&&start| start > 0 && start < steps.len())
+    {
+        shapes.push(Shape::Line {
+            from: [LEFT, y(start)],
+            to: [LEFT + plot_width, y(start)],
+            color: GRAY,
+            width: 1.0,
+            dashed: true,
+        });
+    }
+
+    shapes.push(Shape::Circle {
+        center: [x(schedule.head), y(0)],
+        radius: 4.0,
+        color,
+    });
+    for (i, step) in steps.iter().enumerate() {
+        let from = [x(step.from), y(i)];
+        let to = [x(step.to), y(i + 1)];
+        shapes.push(Shape::Line {
+            from,
+            to,
+            color,
+            width: 2.0,
+            dashed: step.is_return_jump(),
+        });
+
+        let marker = if step.kind == StepKind::DeadlineExpiry {
+            ORANGE
+        } else if step.services_request() {
+            BLUE
+        } else {
+            GRAY
+        };
+        let [tx, ty] = to;
+        // Arrow heads point the way the head moved.
+        let (points, label, anchor) = match step.to.cmp(&step.from) {
+            std::cmp::Ordering::Equal => (
+                [[tx, ty], [tx - 5.0, ty - 10.0], [tx + 5.0, ty - 10.0]],
+                [tx + 8.0, ty + 4.0],
+                Anchor::Start,
+            ),
+            std::cmp::Ordering::Less => (
+                [[tx, ty], [tx + 10.0, ty - 5.0], [tx + 10.0, ty + 5.0]],
+                [tx - 6.0, ty + 4.0],
+                Anchor::End,
+            ),
+            std::cmp::Ordering::Greater => (
+                [[tx, ty], [tx - 10.0, ty - 5.0], [tx - 10.0, ty + 5.0]],
+                [tx + 6.0, ty + 4.0],
+                Anchor::Start,
+            ),
+        };
+        shapes.push(Shape::Triangle {
+            points,
+            color: marker,
+            fill: step.services_request(),
+        });
+        shapes.push(text(label, &step.to.to_string(), 11.0, anchor, false));
+    }
+
+    Figure {
+        width: WIDTH,
+        height: TOP + plot_height + BOTTOM,
+        shapes,
+    }
+}
+
+/// Spacing of the cylinder ticks: 1, 2 or 5 times a power of ten, giving at
+/// most about ten ticks.
+fn tick_step(max_cylinder: u32) -> u32 {
+    let raw = (max_cylinder as f64 / 10.0).max(1.0);
+    let magnitude = 10f64.powf(raw.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|&step| step >= raw)
+        .unwrap_or(raw) as u32
+}
+
+fn line(from: [f32; 2], to: [f32; 2], color: Rgb, width: f32) -> Shape {
+    Shape::Line {
+        from,
+        to,
+        color,
+        width,
+        dashed: false,
+    }
+}
+
+fn text(at: [f32; 2], text: &str, size: f32, anchor: Anchor, bold: bool) -> Shape {
+    Shape::Text {
+        at,
+        text: text.to_owned(),
+        size,
+        color: BLACK,
+        anchor,
+        bold,
+    }
+}
+
+fn hex([r, g, b]: Rgb) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Figure {
+    pub fn to_svg(&self) -> String {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+             viewBox=\"0 0 {w} {h}\" font-family=\"Ubuntu, Helvetica, Arial, sans-serif\">\n\
+             <rect width=\"100%\" height=\"100%\" fill=\"{bg}\"/>\n",
+            w = self.width,
+            h = self.height,
+            bg = hex(WHITE)
+        );
+        for shape in &self.shapes {
+            let element = match shape {
+                Shape::Line {
+                    from: [x1, y1],
+                    to: [x2, y2],
+                    color,
+                    width,
+                    dashed,
+                } => {
+                    let dash = if *dashed {
+                        format!(" stroke-dasharray=\"{} {}\"", DASH[0], DASH[1])
+                    } else {
+                        String::new()
+                    };
+                    format!(
+                        "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"{}\" \
+                         stroke-width=\"{width}\"{dash}/>",
+                        hex(*color)
+                    )
+                }
+                Shape::Triangle {
+                    points,
+                    color,
+                    fill,
+                } => {
+                    let points: Vec<String> =
+                        points.iter().map(|[x, y]| format!("{x},{y}")).collect();
+                    let fill = if *fill { hex(*color) } else { hex(WHITE) };
+                    format!(
+                        "<polygon points=\"{}\" fill=\"{fill}\" stroke=\"{}\" stroke-width=\"1.5\"/>",
+                        points.join(" "),
+                        hex(*color)
+                    )
+                }
+                Shape::Circle {
+                    center: [cx, cy],
+                    radius,
+                    color,
+                } => format!(
+                    "<circle cx=\"{cx}\" cy=\"{cy}\" r=\"{radius}\" fill=\"{}\"/>",
+                    hex(*color)
+                ),
+                Shape::Text {
+                    at: [x, y],
+                    text,
+                    size,
+                    color,
+                    anchor,
+                    bold,
+                } => {
+                    let anchor = match anchor {
+                        Anchor::Start => "start",
+                        Anchor::Middle => "middle",
+                        Anchor::End => "end",
+                    };
+                    let weight = if *bold { " font-weight=\"bold\"" } else { "" };
+                    format!(
+                        "<text x=\"{x}\" y=\"{y}\" font-size=\"{size}\" fill=\"{}\" \
+                         text-anchor=\"{anchor}\"{weight}>{}</text>",
+                        hex(*color),
+                        escape_xml(text)
+                    )
+                }
+            };
+            svg.push_str(&element);
+            svg.push('\n');
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// The figure as a PNG image, with text set in egui's default fonts.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn to_png(&self) -> Result<Vec<u8>, String> {
+        raster::render(self)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod raster {
+    use resvg::{tiny_skia, usvg};
+    use usvg::{TreeParsing, TreeTextToPath};
+
+    use super::Figure;
+
+    /// Draws the figure's SVG, so both exports look the same.
+    pub(super) fn render(figure: &Figure) -> Result<Vec<u8>, String> {
+        let mut fonts = usvg::fontdb::Database::new();
+        for data in egui::FontDefinitions::default().font_data.values() {
+            fonts.load_font_data(data.font.to_vec());
+        }
+        fonts.set_sans_serif_family("Ubuntu");
+
+        let mut tree = usvg::Tree::from_str(&figure.to_svg(), &usvg::Options::default())
+            .map_err(|err| format!("cannot read the figure: {err}"))?;
+        tree.convert_text(&fonts);
+        let tree = resvg::Tree::from_usvg(&tree);
+
+        let mut pixmap =
+            tiny_skia::Pixmap::new(figure.width.ceil() as u32, figure.height.ceil() as u32)
+                .ok_or("the figure is empty")?;
+        tree.render(tiny_skia::Transform::default(), &mut pixmap.as_mut());
+        pixmap.encode_png().map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sched::{Algorithm, Direction, Geometry, Request, Tuning};
+
+    fn figure(cylinders: &[u32]) -> Figure {
+        let schedule = Algorithm::CScan.scheduler(&Tuning::default()).schedule(
+            &Request::queue(cylinders),
+            53,
+            Direction::Right,
+            Geometry::cylinders(199),
+        );
+        schedule_figure("C-SCAN <demo>", &schedule, 199, true, [31, 119, 180])
+    }
+
+    #[test]
+    fn draws_one_row_per_step() {
+        let figure = figure(&[98, 183, 37, 122, 14, 124, 65, 67]);
+        assert_eq!(figure.height, TOP + 10.0 * ROW + BOTTOM);
+        let dashed = figure
+            .shapes
+            .iter()
+            .filter(|shape| matches!(shape, Shape::Line { dashed: true, .. }))
+            .count();
+        assert_eq!(dashed, 1, "only the return jump is dashed");
+
+        let svg = figure.to_svg();
+        assert!(svg.contains("C-SCAN &lt;demo&gt;"));
+        assert!(svg.contains("Total head movement: 382 cylinders"));
+        assert_eq!(svg.matches("<polygon").count(), 10);
+    }
+
+    #[test]
+    fn long_schedules_are_cut_off() {
+        let queue: Vec<u32> = (0..1000).map(|i| i % 200).collect();
+        let figure = figure(&queue);
+        assert_eq!(figure.height, TOP + MAX_ROWS as f32 * ROW + BOTTOM);
+        assert!(figure.to_svg().contains("(first 250 of 1000 steps shown)"));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn png_has_the_figure_size() {
+        let figure = figure(&[98, 183, 37]);
+        let png = figure.to_png().unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+        // The IHDR chunk starts with the big-endian width and height.
+        let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+        let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+        assert_eq!([width as f32, height as f32], [figure.width, figure.height]);
+    }
+}
@@ -1,4 +1,19 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
 mod app;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
+pub mod experiment;
+pub mod figure;
+pub mod metrics;
+pub mod quiz;
+pub mod scenario;
+pub mod sched;
+pub mod share;
+pub mod sim;
+pub mod table;
+pub mod trace;
+#[cfg(target_arch = "wasm32")]
+mod web;
+pub mod workload;
 pub use app::TemplateApp;
@@ -3,6 +3,22 @@
 
 #[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("schedule") {
+        #[cfg(windows)]
+        attach_parent_console();
+        match disk_peek::cli::run(&args[1..]) {
+            Ok(output) => {
+                print!("{output}");
+                std::process::exit(0);
+            }
+            Err(err) => {
+                eprintln!("error: {err}\n\n{}", disk_peek::cli::USAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
 
     let native_options = eframe::NativeOptions {
@@ -19,6 +35,24 @@ fn main() -> eframe::Result<()> {
     )
 }
 
+/// A release build on Windows has no console of its own, so the CLI writes
+/// to the console of the shell that started it, if there is one.
+#[cfg(windows)]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    // SAFETY: AttachConsole takes a plain process id and fails harmlessly
+    // when there is no parent console or one is already attached.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
 fn main() {
     // Redirect `log` message to `console.log` and friends:
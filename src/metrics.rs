@@ -0,0 +1,125 @@
+//! Waiting-time and fairness statistics for schedules and simulations.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::sched::Schedule;
+use crate::sim::Simulation;
+
+/// A request is counted as starved when it waits at least this many times
+/// the mean waiting time.
+pub const STARVATION_FACTOR: f64 = 2.0;
+
+/// Summary of how long requests waited before being serviced.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct WaitStats {
+    pub count: usize,
+    pub mean: f64,
+    pub max: f64,
+    pub std_dev: f64,
+    /// 95th percentile, nearest-rank.
+    pub p95: f64,
+    /// Requests that waited at least [`STARVATION_FACTOR`] times the mean.
+    pub starved: usize,
+}
+
+impl WaitStats {
+    pub fn from_waits(waits: &[f64]) -> Self {
+        if waits.is_empty() {
+            return Self::default();
+        }
+
+        let count = waits.len();
+        let mean = waits.iter().sum::<f64>() / count as f64;
+        let variance = waits.iter().map(|w| (w - mean).powi(2)).sum::<f64>() / count as f64;
+
+        let mut sorted = waits.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let rank = ((0.95 * count as f64).ceil() as usize).clamp(1, count);
+
+        Self {
+            count,
+            mean,
+            max: sorted[count - 1],
+            std_dev: variance.sqrt(),
+            p95: sorted[rank - 1],
+            starved: waits
+                .iter()
+                .filter(|&&w| w > 0.0 && w >= STARVATION_FACTOR * mean)
+                .count(),
+        }
+    }
+}
+
+/// Waiting time of every request of `queue`, measured in head movements made
+/// before the one that services it. Indexed like `queue`; requests that were
+/// never serviced are `None`.
+pub fn step_waits(schedule: &Schedule, queue: &[u32]) -> Vec<Option<f64>> {
+    // Equal cylinders are handed out in queue order.
+    let mut slots: HashMap<u32, VecDeque<usize>> = HashMap::new();
+    for (slot, &cylinder) in queue.iter().enumerate() {
+        slots.entry(cylinder).or_default().push_back(slot);
+    }
+    let mut waits = vec![None; queue.len()];
+    for (i, step) in schedule.steps.iter().enumerate() {
+        if !step.services_request() {
+            continue;
+        }
+        if let Some(slot) = slots.get_mut(&step.to).and_then(VecDeque::pop_front) {
+            waits[slot] = Some(i as f64);
+        }
+    }
+    waits
+}
+
+/// Waiting time of every simulated request in milliseconds, indexed like the
+/// simulated queue.
+pub fn simulated_waits(simulation: &Simulation, count: usize) -> Vec<Option<f64>> {
+    let mut waits = vec![None; count];
+    for service in &simulation.services {
+        if let Some(wait) = waits.get_mut(service.request) {
+            *wait = Some(service.waiting_ms());
+        }
+    }
+    waits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sched::{Direction, DiskScheduler, Geometry, Request, Scan};
+
+    #[test]
+    fn wait_stats() {
+        let stats = WaitStats::from_waits(&[4.0, 1.0, 10.0, 2.0, 3.0]);
+        assert_eq!(stats.count, 5);
+        assert_eq!(stats.mean, 4.0);
+        assert_eq!(stats.max, 10.0);
+        assert_eq!(stats.std_dev, 10f64.sqrt());
+        assert_eq!(stats.p95, 10.0);
+        assert_eq!(stats.starved, 1);
+
+        // Nobody starves when nobody waits.
+        assert_eq!(WaitStats::from_waits(&[0.0, 0.0]).starved, 0);
+        assert_eq!(WaitStats::from_waits(&[]), WaitStats::default());
+
+        let waits: Vec<f64> = (1..=20).map(f64::from).collect();
+        assert_eq!(WaitStats::from_waits(&waits).p95, 19.0);
+    }
+
+    #[test]
+    fn step_waits_count_steps_before_service() {
+        let queue = [60, 10, 60, 90];
+        let schedule = Scan.schedule(
+            &Request::queue(&queue),
+            50,
+            Direction::Left,
+            Geometry::cylinders(99),
+        );
+        // 10, the edge, 60, 60, 90.
+        assert_eq!(
+            step_waits(&schedule, &queue),
+            vec![Some(2.0), Some(0.0), Some(3.0), Some(4.0)]
+        );
+        assert_eq!(step_waits(&schedule, &[60, 10, 30]).last(), Some(&None));
+    }
+}
@@ -0,0 +1,259 @@
+//! Practice problems: a random disk, head and queue for one of the classic
+//! algorithms, and grading of a typed answer against the real schedule.
+
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::sched::{Algorithm, Direction, Geometry, Request, Schedule, Tuning};
+
+/// The algorithms problems are drawn from, the ones textbooks work by hand.
+pub const ALGORITHMS: [Algorithm; 6] = [
+    Algorithm::Fcfs,
+    Algorithm::Sstf,
+    Algorithm::Scan,
+    Algorithm::CScan,
+    Algorithm::Look,
+    Algorithm::CLook,
+];
+
+const CYLINDER_COUNTS: [u32; 3] = [100, 200, 500];
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Problem {
+    pub cylinders: u32,
+    pub head: u32,
+    pub direction: Direction,
+    /// Requested cylinders in arrival order, all different and none under the head.
+    pub queue: Vec<u32>,
+    pub algorithm: Algorithm,
+}
+
+impl Problem {
+    /// A problem with 6 to 10 requests, the same for the same seed.
+    pub fn generate(seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let cylinders = CYLINDER_COUNTS[rng.gen_range(0..CYLINDER_COUNTS.len())];
+        let head = rng.gen_range(0..cylinders);
+        let direction = if rng.gen_bool(0.5) {
+            Direction::Left
+        } else {
+            Direction::Right
+        };
+        let count = rng.gen_range(6..=10);
+        let mut candidates: Vec<u32> = (0..cylinders)
+            .filter(|&cylinder| cylinder != head)
+            .collect();
+        let (queue, _) = candidates.partial_shuffle(&mut rng, count);
+        Self {
+            cylinders,
+            head,
+            direction,
+            queue: queue.to_vec(),
+            algorithm: ALGORITHMS[rng.gen_range(0..ALGORITHMS.len())],
+        }
+    }
+
+    pub fn geometry(&self) -> Geometry {
+        Geometry::cylinders(self.cylinders - 1)
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        Request::queue(&self.queue)
+    }
+
+    /// The schedule the answer is graded against, with the default tuning
+    /// (SSTF breaks ties toward the lower cylinder).
+    pub fn solve(&self) -> Schedule {
+        self.algorithm.scheduler(&Tuning::default()).schedule(
+            &self.requests(),
+            self.head,
+            self.direction,
+            self.geometry(),
+        )
+    }
+
+    /// The problem as it is put to the student.
+    pub fn statement(&self, count_return_jump: bool) -> String {
+        let edge = match self.direction {
+            Direction::Left => 0,
+            Direction::Right => self.cylinders - 1,
+        };
+        let mut statement = format!(
+            "A disk has {} cylinders (0 to {}). The head is at cylinder {} and moving towards \
+             cylinder {edge}. The queue holds requests for cylinders {}, in that order. Using {}, \
+             give the order in which the requests are serviced (requested cylinders only, not \
+             disk edges) and the total head movement in cylinders.",
+            self.cylinders,
+            self.cylinders - 1,
+            self.head,
+            self.queue
+                .iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.algorithm.scheduler(&Tuning::default()).name(),
+        );
+        match self.algorithm {
+            Algorithm::Sstf => {
+                statement.push_str(" Ties go to the lower cylinder.");
+            }
+            Algorithm::CScan | Algorithm::CLook => {
+                statement.push_str(if count_return_jump {
+                    " The return jump counts toward head movement."
+                } else {
+                    " The return jump does not count toward head movement."
+                });
+            }
+            _ => {}
+        }
+        statement
+    }
+
+    /// Checks `order` (serviced cylinders only, without disk edges passed on
+    /// the way) and `total` against the real schedule.
+    pub fn grade(&self, order: &[u32], total: u32, count_return_jump: bool) -> Grade {
+        let schedule = self.solve();
+        let expected_order: Vec<u32> = schedule
+            .steps
+            .iter()
+            .filter(|step| step.services_request())
+            .map(|step| step.to)
+            .collect();
+        let first_mistake = expected_order
+            .iter()
+            .zip(order)
+            .position(|(expected, answer)| expected != answer)
+            .or_else(|| {
+                (order.len() != expected_order.len()).then(|| order.len().min(expected_order.len()))
+            });
+        let first_wrong_step = first_mistake.map(|mistake| {
+            // The step servicing the mistaken request, or the last step for
+            // answers that run past the end of the schedule.
+            schedule
+                .steps
+                .iter()
+                .enumerate()
+                .filter(|(_, step)| step.services_request())
+                .nth(mistake)
+                .map_or(schedule.steps.len().saturating_sub(1), |(i, _)| i)
+        });
+        let expected_total = schedule.total_movement(count_return_jump);
+        Grade {
+            expected_order,
+            expected_total,
+            first_mistake,
+            first_wrong_step,
+            total_correct: total == expected_total,
+            schedule,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Grade {
+    /// The real schedule, edge sweeps and return jumps included.
+    pub schedule: Schedule,
+    pub expected_order: Vec<u32>,
+    pub expected_total: u32,
+    /// Index into the service order of the first answer that differs, `None`
+    /// when the whole order is right.
+    pub first_mistake: Option<usize>,
+    /// Index into `schedule.steps` of the step where the answer goes wrong.
+    pub first_wrong_step: Option<usize>,
+    pub total_correct: bool,
+}
+
+impl Grade {
+    pub fn passed(&self) -> bool {
+        self.first_mistake.is_none() && self.total_correct
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problem(algorithm: Algorithm, cylinders: u32, head: u32, direction: Direction) -> Problem {
+        Problem {
+            cylinders,
+            head,
+            direction,
+            queue: vec![98, 183, 37, 122, 14, 124, 65, 67],
+            algorithm,
+        }
+    }
+
+    #[test]
+    fn same_seed_same_problem() {
+        assert_eq!(
+            Problem::generate(3),
+            Problem {
+                cylinders: 100,
+                head: 91,
+                direction: Direction::Right,
+                queue: vec![65, 23, 54, 21, 66, 30, 80],
+                algorithm: Algorithm::Sstf,
+            }
+        );
+    }
+
+    #[test]
+    fn textbook_answers_pass() {
+        let cscan = problem(Algorithm::CScan, 200, 53, Direction::Right);
+        let order = [65, 67, 98, 122, 124, 183, 14, 37];
+        assert!(cscan.grade(&order, 382, true).passed());
+        assert!(cscan.grade(&order, 183, false).passed());
+
+        let sstf = problem(Algorithm::Sstf, 200, 53, Direction::Right);
+        assert!(sstf
+            .grade(&[65, 67, 37, 14, 98, 122, 124, 183], 236, true)
+            .passed());
+
+        let look = problem(Algorithm::Look, 200, 53, Direction::Left);
+        assert!(look
+            .grade(&[37, 14, 65, 67, 98, 122, 124, 183], 208, true)
+            .passed());
+    }
+
+    #[test]
+    fn cscan_does_not_jump_back_to_nothing() {
+        let problem = Problem {
+            cylinders: 100,
+            head: 98,
+            direction: Direction::Left,
+            queue: vec![28, 97, 33, 37, 38, 14, 23, 59, 42],
+            algorithm: Algorithm::CScan,
+        };
+        let grade = problem.grade(&[97, 59, 42, 38, 37, 33, 28, 23, 14], 84, true);
+        assert!(grade.passed());
+        assert!(grade
+            .schedule
+            .steps
+            .iter()
+            .all(|step| step.services_request()));
+    }
+
+    #[test]
+    fn points_at_the_first_wrong_step() {
+        let cscan = problem(Algorithm::CScan, 200, 53, Direction::Right);
+        let grade = cscan.grade(&[65, 67, 98, 122, 124, 183, 37, 14], 382, true);
+        assert!(!grade.passed());
+        assert!(grade.total_correct);
+        assert_eq!(grade.first_mistake, Some(6));
+        // Past the edge sweep and the return jump, on the step to cylinder 14.
+        assert_eq!(grade.first_wrong_step, Some(8));
+
+        let grade = cscan.grade(&[65, 67], 0, true);
+        assert_eq!(grade.first_mistake, Some(2));
+        assert!(!grade.total_correct);
+    }
+
+    #[test]
+    fn generated_problems_end_on_a_request() {
+        for seed in 0..500 {
+            let schedule = Problem::generate(seed).solve();
+            let last = schedule.steps.last().expect("problems are never empty");
+            assert!(last.services_request(), "seed {seed}");
+        }
+    }
+}
@@ -0,0 +1,499 @@
+//! Named, versioned snapshots of a disk, its request queue and the scheduler
+//! options, saved as JSON documents.
+
+use serde::{Deserialize, Serialize};
+
+use crate::sched::{
+    Algorithm, DeadlineTunables, Direction, Geometry, Op, Platter, Request, Tuning, MAX_CYLINDERS,
+    MAX_REQUESTS,
+};
+use crate::sim::{seek_table, SeekModel};
+
+/// Written into every scenario so later layouts can still read older files.
+pub const FORMAT: &str = "disk-peek-scenario";
+pub const VERSION: u32 = 1;
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Scenario {
+    pub name: String,
+    pub geometry: Geometry,
+    pub head: u32,
+    pub direction: Direction,
+    pub requests: Vec<Request>,
+    /// Free-text note per request, indexed like `requests`; empty means none.
+    pub notes: Vec<String>,
+    /// The algorithms that were being looked at.
+    pub algorithms: Vec<Algorithm>,
+    pub tuning: Tuning,
+    /// Whether the C-SCAN / C-LOOK return jump counts toward head movement.
+    pub count_return_jump: bool,
+    /// Whether requests join the queue at their arrival times.
+    pub simulate: bool,
+}
+
+impl Scenario {
+    pub fn to_json(&self) -> String {
+        let requests = self
+            .requests
+            .iter()
+            .enumerate()
+            .map(|(i, request)| RequestEntry {
+                cylinder: request.cylinder,
+                arrival_ms: request.arrival_ms,
+                sector: request.sector,
+                op: Some(key(request.op)),
+                note: self.notes.get(i).cloned().unwrap_or_default(),
+            })
+            .collect();
+        let file = File {
+            format: FORMAT.to_owned(),
+            version: VERSION,
+            name: self.name.clone(),
+            disk: Disk {
+                cylinders: self.geometry.max_cylinder + 1,
+                platter: self.geometry.platter,
+            },
+            head: self.head,
+            direction: Some(key(self.direction)),
+            requests,
+            algorithms: self
+                .algorithms
+                .iter()
+                .map(|&algorithm| key(algorithm))
+                .collect(),
+            options: Options {
+                tie_break: Some(key(self.tuning.tie_break)),
+                batch_size: Some(self.tuning.batch_size),
+                seek: Some(SeekFile::from(&self.tuning.seek)),
+                deadline: Some(self.tuning.deadline),
+                count_return_jump: Some(self.count_return_jump),
+                simulate: Some(self.simulate),
+            },
+        };
+        // Scenarios hold nothing that JSON cannot represent.
+        serde_json::to_string_pretty(&file).expect("scenario serializes")
+    }
+
+    /// Reads a scenario written by [`Scenario::to_json`]. Everything but the
+    /// disk, head and requests may be left out and falls back to its default.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let header: Header = serde_json::from_str(text).map_err(|err| err.to_string())?;
+        if header.format.as_deref() != Some(FORMAT) {
+            return Err(format!(
+                "not a scenario file (missing \"format\": \"{FORMAT}\")"
+            ));
+        }
+        let version = header.version.ok_or("missing `version`")?;
+        if version > VERSION {
+            return Err(format!(
+                "scenario version {version} is newer than the supported version {VERSION}"
+            ));
+        }
+        let file: File = serde_json::from_str(text).map_err(|err| err.to_string())?;
+
+        let cylinders = file.disk.cylinders;
+        if !(1..=MAX_CYLINDERS).contains(&cylinders) {
+            return Err(format!(
+                "`disk.cylinders` must be between 1 and {MAX_CYLINDERS}"
+            ));
+        }
+        if let Some(platter) = file.disk.platter {
+            if !(1..=Platter::MAX_RPM).contains(&platter.rpm) {
+                return Err(format!(
+                    "`disk.platter.rpm` must be between 1 and {}",
+                    Platter::MAX_RPM
+                ));
+            }
+            if !(1..=Platter::MAX_SECTORS_PER_TRACK).contains(&platter.sectors_per_track) {
+                return Err(format!(
+                    "`disk.platter.sectors_per_track` must be between 1 and {}",
+                    Platter::MAX_SECTORS_PER_TRACK
+                ));
+            }
+        }
+        let geometry = Geometry {
+            max_cylinder: cylinders - 1,
+            platter: file.disk.platter,
+        };
+        let head = file.head;
+        if head > geometry.max_cylinder {
+            return Err(format!("head {head} is outside 0..{cylinders}"));
+        }
+
+        if file.requests.len() > MAX_REQUESTS {
+            return Err(format!(
+                "{} requests is more than the {MAX_REQUESTS} allowed",
+                file.requests.len()
+            ));
+        }
+        let mut requests = Vec::new();
+        let mut notes = Vec::new();
+        for (i, entry) in file.requests.into_iter().enumerate() {
+            let at = |err: String| format!("request {}: {err}", i + 1);
+            let cylinder = entry.cylinder;
+            if cylinder > geometry.max_cylinder {
+                return Err(at(format!("cylinder {cylinder} is outside 0..{cylinders}")));
+            }
+            if let Some(platter) = geometry.platter {
+                if entry.sector >= platter.sectors_per_track {
+                    return Err(at(format!(
+                        "sector {} is outside 0..{}",
+                        entry.sector, platter.sectors_per_track
+                    )));
+                }
+            }
+            if !entry.arrival_ms.is_finite() || entry.arrival_ms < 0.0 {
+                return Err(at(format!("invalid arrival time {}", entry.arrival_ms)));
+            }
+            let op = match entry.op {
+                None => Op::Read,
+                Some(op) => parse_op(&op).map_err(at)?,
+            };
+            requests.push(
+                Request::arriving_at(cylinder, entry.arrival_ms)
+                    .on_sector(entry.sector)
+                    .with_op(op),
+            );
+            notes.push(entry.note);
+        }
+
+        let direction = match file.direction {
+            Some(direction) => direction.parse()?,
+            None => Direction::Left,
+        };
+        let algorithms = file
+            .algorithms
+            .iter()
+            .map(|algorithm| algorithm.parse())
+            .collect::<Result<_, _>>()?;
+
+        let options = file.options;
+        let mut tuning = Tuning::default();
+        if let Some(tie_break) = options.tie_break {
+            tuning.tie_break = tie_break.parse()?;
+        }
+        if let Some(batch_size) = options.batch_size {
+            tuning.batch_size = batch_size.max(1);
+        }
+        if let Some(seek) = options.seek {
+            tuning.seek = seek.try_into()?;
+        }
+        if let Some(deadline) = options.deadline {
+            tuning.deadline = deadline;
+        }
+
+        Ok(Self {
+            name: file.name,
+            geometry,
+            head,
+            direction,
+            requests,
+            notes,
+            algorithms,
+            tuning,
+            count_return_jump: options.count_return_jump.unwrap_or(true),
+            simulate: options.simulate.unwrap_or(false),
+        })
+    }
+}
+
+/// Read first, so files that are not scenarios at all, or are too new, get a
+/// clearer error than a missing field.
+#[derive(Deserialize)]
+struct Header {
+    format: Option<String>,
+    version: Option<u32>,
+}
+
+/// The layout of a scenario file. Enums are written as the kebab-case names
+/// their `FromStr` impls read, not in the form the app persists them in.
+#[derive(Serialize, Deserialize)]
+struct File {
+    format: String,
+    version: u32,
+    #[serde(default)]
+    name: String,
+    disk: Disk,
+    head: u32,
+    #[serde(default)]
+    direction: Option<String>,
+    requests: Vec<RequestEntry>,
+    #[serde(default)]
+    algorithms: Vec<String>,
+    #[serde(default)]
+    options: Options,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Disk {
+    cylinders: u32,
+    #[serde(default)]
+    platter: Option<Platter>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RequestEntry {
+    cylinder: u32,
+    #[serde(default)]
+    arrival_ms: f64,
+    #[serde(default)]
+    sector: u32,
+    #[serde(default)]
+    op: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    note: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct Options {
+    tie_break: Option<String>,
+    batch_size: Option<usize>,
+    seek: Option<SeekFile>,
+    deadline: Option<DeadlineTunables>,
+    count_return_jump: Option<bool>,
+    simulate: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "model", rename_all = "kebab-case")]
+enum SeekFile {
+    Linear {
+        overhead_ms: f64,
+        per_cylinder_ms: f64,
+    },
+    SqrtLinear {
+        settle_ms: f64,
+        sqrt_ms: f64,
+        coast_from: u32,
+        per_cylinder_ms: f64,
+    },
+    /// `[distance, ms]` pairs.
+    Table { points: Vec<(u32, f64)> },
+}
+
+impl From<&SeekModel> for SeekFile {
+    fn from(seek: &SeekModel) -> Self {
+        match *seek {
+            SeekModel::Linear {
+                overhead_ms,
+                per_cylinder_ms,
+            } => SeekFile::Linear {
+                overhead_ms,
+                per_cylinder_ms,
+            },
+            SeekModel::SqrtLinear {
+                settle_ms,
+                sqrt_ms,
+                coast_from,
+                per_cylinder_ms,
+            } => SeekFile::SqrtLinear {
+                settle_ms,
+                sqrt_ms,
+                coast_from,
+                per_cylinder_ms,
+            },
+            SeekModel::Table(ref points) => SeekFile::Table {
+                points: points.clone(),
+            },
+        }
+    }
+}
+
+impl TryFrom<SeekFile> for SeekModel {
+    type Error = String;
+
+    fn try_from(seek: SeekFile) -> Result<Self, Self::Error> {
+        Ok(match seek {
+            SeekFile::Linear {
+                overhead_ms,
+                per_cylinder_ms,
+            } => SeekModel::Linear {
+                overhead_ms,
+                per_cylinder_ms,
+            },
+            SeekFile::SqrtLinear {
+                settle_ms,
+                sqrt_ms,
+                coast_from,
+                per_cylinder_ms,
+            } => SeekModel::SqrtLinear {
+                settle_ms,
+                sqrt_ms,
+                coast_from,
+                per_cylinder_ms,
+            },
+            SeekFile::Table { points } => {
+                if points.is_empty() {
+                    return Err("seek table has no points".to_owned());
+                }
+                SeekModel::Table(seek_table(points).map_err(|err| format!("seek table: {err}"))?)
+            }
+        })
+    }
+}
+
+/// Kebab-case name of an enum variant, which its `FromStr` impl accepts.
+pub(crate) fn key(value: impl std::fmt::Debug) -> String {
+    let mut key = String::new();
+    for c in format!("{value:?}").chars() {
+        if c.is_ascii_uppercase() && !key.is_empty() {
+            key.push('-');
+        }
+        key.push(c.to_ascii_lowercase());
+    }
+    key
+}
+
+fn parse_op(op: &str) -> Result<Op, String> {
+    match op.to_ascii_lowercase().as_str() {
+        "read" | "r" => Ok(Op::Read),
+        "write" | "w" => Ok(Op::Write),
+        _ => Err(format!("unknown op `{op}` (expected `read` or `write`)")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sched::TieBreak;
+
+    fn scenario() -> Scenario {
+        Scenario {
+            name: "Textbook \"C-SCAN\"".to_owned(),
+            geometry: Geometry {
+                max_cylinder: 199,
+                platter: Some(Platter::default()),
+            },
+            head: 53,
+            direction: Direction::Right,
+            requests: vec![
+                Request::new(98),
+                Request::arriving_at(183, 12.5)
+                    .on_sector(7)
+                    .with_op(Op::Write),
+            ],
+            notes: vec![String::new(), "slow one".to_owned()],
+            algorithms: vec![Algorithm::CScan, Algorithm::NStepScan],
+            tuning: Tuning {
+                tie_break: TieBreak::DirectionOfTravel,
+                seek: SeekModel::table(),
+                batch_size: 3,
+                deadline: DeadlineTunables {
+                    fifo_batch: 4,
+                    ..Default::default()
+                },
+            },
+            count_return_jump: false,
+            simulate: true,
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let scenario = scenario();
+        assert_eq!(Scenario::from_json(&scenario.to_json()), Ok(scenario));
+    }
+
+    #[test]
+    fn reads_a_minimal_file_with_defaults() {
+        let text = r#"{
+            "format": "disk-peek-scenario",
+            "version": 1,
+            "disk": { "cylinders": 200 },
+            "head": 53,
+            "requests": [{ "cylinder": 98 }, { "cylinder": 37, "op": "w" }],
+            "options": { "seek": { "model": "sqrt-linear", "settle_ms": 1, "sqrt_ms": 0.4,
+                                   "coast_from": 200, "per_cylinder_ms": 0.015 },
+                         "deadline": { "fifo_batch": 1 } }
+        }"#;
+        let scenario = Scenario::from_json(text).unwrap();
+        assert_eq!(scenario.geometry, Geometry::cylinders(199));
+        assert_eq!(scenario.direction, Direction::Left);
+        assert_eq!(
+            scenario.requests,
+            vec![Request::new(98), Request::new(37).with_op(Op::Write)]
+        );
+        assert_eq!(scenario.notes, vec![String::new(), String::new()]);
+        assert_eq!(scenario.tuning.seek, SeekModel::sqrt_linear());
+        assert_eq!(scenario.tuning.deadline.fifo_batch, 1);
+        assert_eq!(scenario.tuning.deadline.read_expire_ms, 500.0);
+        assert!(scenario.count_return_jump);
+        assert!(!scenario.simulate);
+    }
+
+    #[test]
+    fn rejects_bad_files() {
+        let with = |fields: &str| {
+            format!(r#"{{"format": "disk-peek-scenario", "disk": {{"cylinders": 100}}, {fields}}}"#)
+        };
+        let err = |text: &str| Scenario::from_json(text).unwrap_err();
+
+        assert!(err(r#"{"version": 1}"#).starts_with("not a scenario file"));
+        assert!(err(&with(r#""version": 2, "head": 0, "requests": []"#)).contains("newer"));
+        assert!(err(&with(r#""version": 1, "head": 100, "requests": []"#)).contains("head 100"));
+        assert!(err(&with(
+            r#""version": 1, "head": 0, "requests": [{"cylinder": 100}]"#
+        ))
+        .starts_with("request 1:"));
+        assert!(err(&with(r#""version": 1, "requests": []"#)).contains("head"));
+        assert_eq!(
+            err(&with(
+                r#""version": 1, "head": 0, "requests": [{"cylinder": 1, "arrival_ms": -1}]"#
+            )),
+            "request 1: invalid arrival time -1"
+        );
+
+        let disk = |disk: &str, requests: &str| {
+            format!(
+                r#"{{"format": "disk-peek-scenario", "version": 1, "disk": {disk}, "head": 0,
+                    "requests": [{requests}]}}"#
+            )
+        };
+        let platter = r#"{"cylinders": 100, "platter": {"rpm": 7200, "sectors_per_track": 64}}"#;
+        assert!(Scenario::from_json(&disk(platter, r#"{"cylinder": 1, "sector": 63}"#)).is_ok());
+        assert_eq!(
+            err(&disk(platter, r#"{"cylinder": 1, "sector": 64}"#)),
+            "request 1: sector 64 is outside 0..64"
+        );
+        let no_sectors = r#"{"cylinders": 100, "platter": {"rpm": 7200, "sectors_per_track": 0}}"#;
+        assert!(err(&disk(no_sectors, "")).contains("sectors_per_track"));
+        let fast = r#"{"cylinders": 100, "platter": {"rpm": 90000, "sectors_per_track": 64}}"#;
+        assert!(err(&disk(fast, "")).contains("rpm"));
+        assert!(err(&disk(r#"{"cylinders": 0}"#, "")).contains("between 1 and"));
+        assert!(err(&disk(r#"{"cylinders": 4294967295}"#, "")).contains("between 1 and"));
+        let requests = vec![r#"{"cylinder": 1}"#; MAX_REQUESTS + 1].join(",");
+        assert_eq!(
+            err(&disk(r#"{"cylinders": 100}"#, &requests)),
+            "10001 requests is more than the 10000 allowed"
+        );
+
+        let seek = |points: &str| {
+            err(&with(&format!(
+                r#""version": 1, "head": 0, "requests": [],
+                   "options": {{"seek": {{"model": "table", "points": {points}}}}}"#
+            )))
+        };
+        assert_eq!(
+            seek("[[1, 1], [1, 2]]"),
+            "seek table: distance 1 is listed twice"
+        );
+        assert_eq!(
+            seek("[[1, 1], [10, 3], [100, 2]]"),
+            "seek table: seeking 100 cylinders takes less time than seeking 10"
+        );
+        assert_eq!(
+            seek("[[1, -1]]"),
+            "seek table: distance 1 has an invalid seek time"
+        );
+        assert_eq!(seek("[]"), "seek table has no points");
+
+        // Deeply nested values do not overflow the stack.
+        let deep = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        assert!(Scenario::from_json(&deep).is_err());
+        assert!(Scenario::from_json(&with(&format!(
+            r#""version": 1, "head": 0, "requests": [], "extra": {deep}"#
+        )))
+        .is_ok());
+    }
+}
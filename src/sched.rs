@@ -0,0 +1,1587 @@
+//! Disk scheduling algorithms, independent of the egui front-end.
+//!
+//! Every algorithm implements [`DiskScheduler`], which turns a queue of pending
+//! [`Request`]s into a [`Schedule`]: the order in which the head visits cylinders
+//! and the seek performed for each step.
+
+use crate::sim::{access, SeekModel};
+
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Direction {
+    Right,
+    Left,
+}
+
+impl std::str::FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "left" | "l" | "down" => Ok(Direction::Left),
+            "right" | "r" | "up" => Ok(Direction::Right),
+            _ => Err(format!(
+                "unknown direction `{s}` (expected `left` or `right`)"
+            )),
+        }
+    }
+}
+
+/// How SSTF picks between two pending requests that are equally close to the head.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum TieBreak {
+    /// Prefer the request with the lower cylinder number.
+    LowerCylinder,
+    /// Prefer the request that keeps the head moving the way it last moved
+    /// (the configured scan direction before the first move).
+    DirectionOfTravel,
+    /// Prefer the request that was entered first in the sequence.
+    FirstArrived,
+}
+
+impl std::str::FromStr for TieBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "lower" | "lower-cylinder" => Ok(TieBreak::LowerCylinder),
+            "direction" | "direction-of-travel" => Ok(TieBreak::DirectionOfTravel),
+            "first" | "first-arrived" => Ok(TieBreak::FirstArrived),
+            _ => Err(format!(
+                "unknown tie break `{s}` (expected `lower`, `direction` or `first`)"
+            )),
+        }
+    }
+}
+
+/// Whether a request reads from or writes to the disk.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum Op {
+    #[default]
+    Read,
+    Write,
+}
+
+/// A pending I/O request waiting in the queue.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Debug, Clone, Copy)]
+pub struct Request {
+    pub cylinder: u32,
+    /// When the request reaches the queue, in milliseconds. Only the
+    /// simulator looks at this; the schedulers see a queue that is already full.
+    #[serde(default)]
+    pub arrival_ms: f64,
+    /// The sector on the track that is read, when the platter is modelled.
+    #[serde(default)]
+    pub sector: u32,
+    /// Only the deadline scheduler treats reads and writes differently.
+    #[serde(default)]
+    pub op: Op,
+}
+
+impl Request {
+    pub fn new(cylinder: u32) -> Self {
+        Self {
+            cylinder,
+            arrival_ms: 0.0,
+            sector: 0,
+            op: Op::Read,
+        }
+    }
+
+    pub fn arriving_at(cylinder: u32, arrival_ms: f64) -> Self {
+        Self {
+            cylinder,
+            arrival_ms,
+            sector: 0,
+            op: Op::Read,
+        }
+    }
+
+    pub fn on_sector(self, sector: u32) -> Self {
+        Self { sector, ..self }
+    }
+
+    pub fn with_op(self, op: Op) -> Self {
+        Self { op, ..self }
+    }
+
+    /// Builds a queue from bare cylinder numbers, in arrival order.
+    pub fn queue(cylinders: &[u32]) -> Vec<Request> {
+        cylinders.iter().copied().map(Request::new).collect()
+    }
+}
+
+/// Splits a list on commas, semicolons and/or whitespace.
+pub(crate) fn list_tokens(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+}
+
+/// Parses cylinder numbers separated by commas, semicolons and/or whitespace.
+pub fn parse_cylinders(text: &str) -> Result<Vec<u32>, String> {
+    list_tokens(text)
+        .map(|token| {
+            token
+                .parse()
+                .map_err(|_| format!("`{token}` is not a cylinder number"))
+        })
+        .collect()
+}
+
+/// Like [`parse_cylinders`], but each entry may carry an arrival time in
+/// milliseconds as `cylinder@time`, e.g. `98@0, 183@12.5`.
+pub fn parse_requests(text: &str) -> Result<Vec<Request>, String> {
+    list_tokens(text)
+        .map(|token| {
+            let (op, rest) = match token.as_bytes().first() {
+                Some(b'w' | b'W') => (Op::Write, &token[1..]),
+                Some(b'r' | b'R') => (Op::Read, &token[1..]),
+                _ => (Op::Read, token),
+            };
+            let (location, arrival) = rest.split_once('@').unwrap_or((rest, "0"));
+            let (cylinder, sector) = location.split_once(':').unwrap_or((location, "0"));
+            let cylinder = cylinder
+                .parse()
+                .map_err(|_| format!("`{token}` is not a cylinder number"))?;
+            let sector = sector
+                .parse()
+                .map_err(|_| format!("`{token}` has an invalid sector"))?;
+            let arrival_ms: f64 = arrival
+                .parse()
+                .ok()
+                .filter(|ms: &f64| ms.is_finite() && *ms >= 0.0)
+                .ok_or_else(|| format!("`{token}` has an invalid arrival time"))?;
+            Ok(Request::arriving_at(cylinder, arrival_ms)
+                .on_sector(sector)
+                .with_op(op))
+        })
+        .collect()
+}
+
+/// Writes `requests` back in the form [`parse_requests`] reads, leaving out
+/// sectors and arrival times when none of the requests have one.
+pub fn format_requests(requests: &[Request], separator: &str) -> String {
+    let with_arrivals = requests.iter().any(|r| r.arrival_ms > 0.0);
+    let with_sectors = requests.iter().any(|r| r.sector > 0);
+    let requests: Vec<String> = requests
+        .iter()
+        .map(|request| {
+            let mut text = match request.op {
+                Op::Read => String::new(),
+                Op::Write => "w".to_owned(),
+            };
+            text.push_str(&request.cylinder.to_string());
+            if with_sectors {
+                text.push_str(&format!(":{}", request.sector));
+            }
+            if with_arrivals {
+                text.push_str(&format!("@{}", request.arrival_ms));
+            }
+            text
+        })
+        .collect();
+    requests.join(separator)
+}
+
+/// The most cylinders a disk loaded from a file or link may have.
+pub const MAX_CYLINDERS: u32 = 1_000_000;
+/// The most requests a workload loaded from a file, link or trace may hold.
+pub const MAX_REQUESTS: usize = 10_000;
+
+/// Shape of the disk the head moves over.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Geometry {
+    /// The last cylinder of the disk; SCAN and C-SCAN sweep out to it.
+    pub max_cylinder: u32,
+    /// How the platter spins under the head; `None` models the radial axis only.
+    #[serde(default)]
+    pub platter: Option<Platter>,
+}
+
+impl Geometry {
+    /// A disk whose platter is not modelled.
+    pub fn cylinders(max_cylinder: u32) -> Self {
+        Self {
+            max_cylinder,
+            platter: None,
+        }
+    }
+}
+
+/// The rotating part of the disk. Sector 0 is under the head at time 0.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Platter {
+    pub rpm: u32,
+    pub sectors_per_track: u32,
+}
+
+impl Default for Platter {
+    fn default() -> Self {
+        Self {
+            rpm: 7200,
+            sectors_per_track: 64,
+        }
+    }
+}
+
+impl Platter {
+    /// The fastest spindle the app and scenario files accept.
+    pub const MAX_RPM: u32 = 20_000;
+    pub const MAX_SECTORS_PER_TRACK: u32 = 1024;
+
+    pub fn revolution_ms(self) -> f64 {
+        60_000.0 / self.rpm.max(1) as f64
+    }
+
+    /// Time for one sector to pass under the head, which is also the time to read it.
+    pub fn sector_ms(self) -> f64 {
+        self.revolution_ms() / self.sectors_per_track.max(1) as f64
+    }
+
+    /// The angular position under the head at `time_ms`, in sectors.
+    pub fn position_at(self, time_ms: f64) -> f64 {
+        (time_ms / self.sector_ms()).rem_euclid(self.sectors_per_track.max(1) as f64)
+    }
+
+    /// How long a head that is in place at `time_ms` waits for `sector` to come round.
+    pub fn latency_ms(self, sector: u32, time_ms: f64) -> f64 {
+        let track = self.sectors_per_track.max(1) as f64;
+        let ahead = (sector as f64 - self.position_at(time_ms)).rem_euclid(track);
+        // Finishing one sector and starting the next should not cost a full turn
+        // just because of rounding.
+        if track - ahead < 1e-6 {
+            0.0
+        } else {
+            ahead * self.sector_ms()
+        }
+    }
+}
+
+/// Why the head moved to a cylinder.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum StepKind {
+    /// Servicing a request from the queue.
+    Request,
+    /// Sweeping out to a disk edge that nobody asked for, as SCAN and C-SCAN do.
+    EdgeSweep,
+    /// The C-SCAN / C-LOOK jump back to the far end of the disk. Textbooks
+    /// disagree on whether it counts toward head movement.
+    ReturnJump { lands_on_request: bool },
+    /// Servicing a request out of sorted order because its deadline expired.
+    DeadlineExpiry,
+}
+
+/// One movement of the head from a cylinder to the next one it visits.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Step {
+    pub from: u32,
+    pub to: u32,
+    pub distance: u32,
+    pub kind: StepKind,
+}
+
+impl StepKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            StepKind::Request => "Request",
+            StepKind::EdgeSweep => "Edge Sweep",
+            StepKind::ReturnJump { .. } => "Return Jump",
+            StepKind::DeadlineExpiry => "Deadline Expiry",
+        }
+    }
+
+    /// The label in step tables, which also tells when a return jump lands
+    /// on a request.
+    pub fn step_label(self) -> &'static str {
+        match self {
+            StepKind::ReturnJump {
+                lands_on_request: true,
+            } => "Return Jump + Request",
+            kind => kind.label(),
+        }
+    }
+}
+
+impl Step {
+    /// Moving from `from` to service a request on `to`.
+    fn request(from: u32, to: u32) -> Self {
+        Self {
+            from,
+            to,
+            distance: from.abs_diff(to),
+            kind: StepKind::Request,
+        }
+    }
+
+    /// Whether the head services a queued request at the end of this step.
+    pub fn services_request(&self) -> bool {
+        matches!(
+            self.kind,
+            StepKind::Request
+                | StepKind::DeadlineExpiry
+                | StepKind::ReturnJump {
+                    lands_on_request: true
+                }
+        )
+    }
+
+    pub fn is_return_jump(&self) -> bool {
+        matches!(self.kind, StepKind::ReturnJump { .. })
+    }
+
+    /// The distance in step tables, marked when the total leaves it out.
+    pub fn distance_label(&self, count_return_jump: bool) -> String {
+        if self.is_return_jump() && !count_return_jump {
+            format!("{} (not counted)", self.distance)
+        } else {
+            self.distance.to_string()
+        }
+    }
+
+    /// The distance this step adds to the total head movement.
+    pub fn counted_distance(&self, count_return_jump: bool) -> u32 {
+        if self.is_return_jump() && !count_return_jump {
+            0
+        } else {
+            self.distance
+        }
+    }
+}
+
+/// The result of running a scheduler over a request queue.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct Schedule {
+    /// Where the head started.
+    pub head: u32,
+    /// Cylinders in the order the head visits them.
+    pub order: Vec<u32>,
+    /// The seek performed to reach each entry of `order`.
+    pub steps: Vec<Step>,
+    /// Indices into `steps` where the batching schedulers start a new batch.
+    #[serde(default)]
+    pub batch_starts: Vec<usize>,
+}
+
+impl Schedule {
+    /// An empty schedule for a head parked at `head`.
+    pub fn new(head: u32) -> Self {
+        Self {
+            head,
+            order: Vec::new(),
+            steps: Vec::new(),
+            batch_starts: Vec::new(),
+        }
+    }
+
+    /// Builds the per-step trace for a head starting at `head` and servicing `order`.
+    pub fn from_order(head: u32, order: Vec<u32>) -> Self {
+        let mut schedule = Self::new(head);
+        schedule.extend_requests(order);
+        schedule
+    }
+
+    /// Moves the head from wherever it is now to `to`.
+    pub fn visit(&mut self, to: u32, kind: StepKind) {
+        let from = self.order.last().copied().unwrap_or(self.head);
+        self.order.push(to);
+        self.steps.push(Step {
+            from,
+            to,
+            distance: from.abs_diff(to),
+            kind,
+        });
+    }
+
+    /// Services `cylinders` in the given order.
+    pub fn extend_requests(&mut self, cylinders: impl IntoIterator<Item = u32>) {
+        for cylinder in cylinders {
+            self.visit(cylinder, StepKind::Request);
+        }
+    }
+
+    /// Jumps to the first of `cylinders` and services the rest in order.
+    pub fn jump_back(&mut self, cylinders: impl IntoIterator<Item = u32>) {
+        let mut cylinders = cylinders.into_iter();
+        if let Some(first) = cylinders.next() {
+            self.visit(
+                first,
+                StepKind::ReturnJump {
+                    lands_on_request: true,
+                },
+            );
+            self.extend_requests(cylinders);
+        }
+    }
+
+    /// Where the head is now.
+    pub fn position(&self) -> u32 {
+        self.order.last().copied().unwrap_or(self.head)
+    }
+
+    /// Starts a new batch and replays the steps of `batch`, which must start
+    /// where this schedule ends.
+    pub fn append_batch(&mut self, batch: Schedule) {
+        self.batch_starts.push(self.steps.len());
+        for step in batch.steps {
+            self.visit(step.to, step.kind);
+        }
+    }
+
+    /// Total number of cylinders the head travelled, optionally leaving out
+    /// the C-SCAN / C-LOOK return jump.
+    pub fn total_movement(&self, count_return_jump: bool) -> u32 {
+        self.steps
+            .iter()
+            .map(|step| step.counted_distance(count_return_jump))
+            .sum()
+    }
+
+    /// Number of times the head changes its direction of travel.
+    pub fn direction_reversals(&self) -> usize {
+        let mut reversals = 0;
+        let mut travel = None;
+        for step in &self.steps {
+            let now = match step.to.cmp(&step.from) {
+                std::cmp::Ordering::Less => Direction::Left,
+                std::cmp::Ordering::Greater => Direction::Right,
+                std::cmp::Ordering::Equal => continue,
+            };
+            if travel.is_some_and(|before| before != now) {
+                reversals += 1;
+            }
+            travel = Some(now);
+        }
+        reversals
+    }
+}
+
+pub trait DiskScheduler {
+    /// Human readable name, used for tabs and reports.
+    fn name(&self) -> &'static str;
+
+    /// Orders `queue` for a head starting at `head` and initially moving in `direction`.
+    fn schedule(
+        &self,
+        queue: &[Request],
+        head: u32,
+        direction: Direction,
+        geometry: Geometry,
+    ) -> Schedule;
+
+    /// Like [`DiskScheduler::schedule`], for a head that sets off `start_ms`
+    /// into the run. Only schedulers that look at the platter care.
+    fn schedule_from(
+        &self,
+        queue: &[Request],
+        head: u32,
+        direction: Direction,
+        geometry: Geometry,
+        _start_ms: f64,
+    ) -> Schedule {
+        self.schedule(queue, head, direction, geometry)
+    }
+
+    /// How the simulator should freeze arriving requests into batches that
+    /// are serviced one after the other; `None` lets every pending request in.
+    fn batching(&self) -> Option<Batching> {
+        None
+    }
+
+    /// Whether [`DiskScheduler::schedule_from`] already honours the arrival
+    /// times in `queue` and keeps its own state from one dispatch to the next.
+    /// The simulator then follows its plan for the whole queue instead of
+    /// planning again whenever requests arrive.
+    fn plans_arrivals(&self) -> bool {
+        false
+    }
+
+    /// The first move of [`DiskScheduler::schedule_from`], for schedulers
+    /// that pick one request at a time and can work it out without planning
+    /// the rest of the queue. The simulator then asks for one move at a time.
+    /// `None` when the scheduler has no such shortcut.
+    fn next_step(
+        &self,
+        _queue: &[Request],
+        _head: u32,
+        _direction: Direction,
+        _geometry: Geometry,
+        _start_ms: f64,
+    ) -> Option<Step> {
+        None
+    }
+}
+
+/// Which pending requests make up the next batch.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Batching {
+    /// The oldest pending requests, at most this many.
+    Oldest(usize),
+    /// Every request pending when the batch starts.
+    AllPending,
+}
+
+/// The algorithms that ship with disk-peek.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Algorithm {
+    Fcfs,
+    Sstf,
+    Scan,
+    CScan,
+    Look,
+    CLook,
+    Sptf,
+    NStepScan,
+    FScan,
+    Deadline,
+}
+
+/// Settings that only some of the algorithms look at.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Debug, Clone)]
+pub struct Tuning {
+    /// Used by SSTF.
+    pub tie_break: TieBreak,
+    /// Used by SPTF and deadline to tell how long their decisions take.
+    pub seek: SeekModel,
+    /// Requests per N-Step SCAN batch.
+    pub batch_size: usize,
+    pub deadline: DeadlineTunables,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            tie_break: TieBreak::LowerCylinder,
+            seek: SeekModel::default(),
+            batch_size: 4,
+            deadline: DeadlineTunables::default(),
+        }
+    }
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 10] = [
+        Algorithm::Fcfs,
+        Algorithm::Sstf,
+        Algorithm::Scan,
+        Algorithm::CScan,
+        Algorithm::Look,
+        Algorithm::CLook,
+        Algorithm::Sptf,
+        Algorithm::NStepScan,
+        Algorithm::FScan,
+        Algorithm::Deadline,
+    ];
+
+    /// Returns the scheduler for this algorithm, configured from `tuning`.
+    pub fn scheduler(self, tuning: &Tuning) -> Box<dyn DiskScheduler> {
+        let Tuning {
+            tie_break,
+            batch_size,
+            ..
+        } = *tuning;
+        match self {
+            Algorithm::Fcfs => Box::new(Fcfs),
+            Algorithm::Sstf => Box::new(Sstf { tie_break }),
+            Algorithm::Scan => Box::new(Scan),
+            Algorithm::CScan => Box::new(CScan),
+            Algorithm::Look => Box::new(Look),
+            Algorithm::CLook => Box::new(CLook),
+            Algorithm::Sptf => Box::new(Sptf {
+                seek: tuning.seek.clone(),
+            }),
+            Algorithm::NStepScan => Box::new(NStepScan { batch_size }),
+            Algorithm::FScan => Box::new(FScan),
+            Algorithm::Deadline => Box::new(Deadline {
+                seek: tuning.seek.clone(),
+                tunables: tuning.deadline,
+            }),
+        }
+    }
+}
+
+impl std::str::FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+            "fcfs" | "fifo" => Ok(Algorithm::Fcfs),
+            "sstf" => Ok(Algorithm::Sstf),
+            "scan" => Ok(Algorithm::Scan),
+            "cscan" => Ok(Algorithm::CScan),
+            "look" => Ok(Algorithm::Look),
+            "clook" => Ok(Algorithm::CLook),
+            "sptf" | "satf" => Ok(Algorithm::Sptf),
+            "nstep" | "nstepscan" => Ok(Algorithm::NStepScan),
+            "fscan" => Ok(Algorithm::FScan),
+            "deadline" | "mqdeadline" => Ok(Algorithm::Deadline),
+            _ => Err(format!(
+                "unknown algorithm `{s}` (expected one of fcfs, sstf, scan, cscan, look, clook, \
+                 sptf, nstep, fscan, deadline)"
+            )),
+        }
+    }
+}
+
+/// Splits the cylinders of `queue` into those below and above the head, both
+/// sorted ascending. Requests on the head's own cylinder go with the side the
+/// head is moving towards, so they are serviced first.
+fn split_at_head(queue: &[Request], head: u32, direction: Direction) -> (Vec<u32>, Vec<u32>) {
+    let mut sorted: Vec<u32> = queue.iter().map(|request| request.cylinder).collect();
+    sorted.sort();
+    let at = match direction {
+        Direction::Left => sorted.partition_point(|&cylinder| cylinder <= head),
+        Direction::Right => sorted.partition_point(|&cylinder| cylinder < head),
+    };
+    let right = sorted.split_off(at);
+    (sorted, right)
+}
+
+/// First-Come First-Served: services the requests in the order they were entered.
+pub struct Fcfs;
+
+impl DiskScheduler for Fcfs {
+    fn name(&self) -> &'static str {
+        "First Come First Served"
+    }
+
+    fn schedule(
+        &self,
+        queue: &[Request],
+        head: u32,
+        _direction: Direction,
+        _geometry: Geometry,
+    ) -> Schedule {
+        let order = queue.iter().map(|request| request.cylinder).collect();
+        Schedule::from_order(head, order)
+    }
+
+    fn next_step(
+        &self,
+        queue: &[Request],
+        head: u32,
+        _direction: Direction,
+        _geometry: Geometry,
+        _start_ms: f64,
+    ) -> Option<Step> {
+        queue.first().map(|next| Step::request(head, next.cylinder))
+    }
+}
+
+/// Shortest Seek Time First: repeatedly services the pending request closest
+/// to the head's *current* position, using `tie_break` when two are equally close.
+pub struct Sstf {
+    pub tie_break: TieBreak,
+}
+
+impl DiskScheduler for Sstf {
+    fn name(&self) -> &'static str {
+        "Shortest Seek Time First"
+    }
+
+    fn schedule(
+        &self,
+        queue: &[Request],
+        head: u32,
+        direction: Direction,
+        _geometry: Geometry,
+    ) -> Schedule {
+        let mut pending = Self::pending(queue);
+        let mut order: Vec<u32> = Vec::new();
+        let mut pos = head;
+        let mut travel = direction;
+
+        while !pending.is_empty() {
+            let (_, next) = pending.remove(self.nearest(&pending, pos, travel));
+            match next.cmp(&pos) {
+                std::cmp::Ordering::Less => travel = Direction::Left,
+                std::cmp::Ordering::Greater => travel = Direction::Right,
+                std::cmp::Ordering::Equal => {}
+            }
+            pos = next;
+            order.push(next);
+        }
+        Schedule::from_order(head, order)
+    }
+
+    fn next_step(
+        &self,
+        queue: &[Request],
+        head: u32,
+        direction: Direction,
+        _geometry: Geometry,
+        _start_ms: f64,
+    ) -> Option<Step> {
+        let pending = Self::pending(queue);
+        let (_, to) = *pending.get(self.nearest(&pending, head, direction))?;
+        Some(Step::request(head, to))
+    }
+}
+
+impl Sstf {
+    /// The cylinders of `queue` with their positions in it.
+    fn pending(queue: &[Request]) -> Vec<(usize, u32)> {
+        queue
+            .iter()
+            .map(|request| request.cylinder)
+            .enumerate()
+            .collect()
+    }
+
+    /// Position in `pending` of the request closest to `pos`.
+    fn nearest(&self, pending: &[(usize, u32)], pos: u32, travel: Direction) -> usize {
+        let mut best = 0;
+        for (i, &(cur_idx, cur)) in pending.iter().enumerate().skip(1) {
+            let (best_idx, best_val) = pending[best];
+            let cur_dist = cur.abs_diff(pos);
+            let best_dist = best_val.abs_diff(pos);
+
+            let better = if cur_dist != best_dist {
+                cur_dist < best_dist
+            } else {
+                match self.tie_break {
+                    TieBreak::LowerCylinder => cur < best_val,
+                    TieBreak::DirectionOfTravel => match travel {
+                        Direction::Left => cur < best_val,
+                        Direction::Right => cur > best_val,
+                    },
+                    TieBreak::FirstArrived => cur_idx < best_idx,
+                }
+            };
+            if better {
+                best = i;
+            }
+        }
+        best
+    }
+}
+
+/// SCAN (elevator): sweeps to the disk edge in `direction`, then reverses.
+/// Like C-SCAN, it stops at the last request instead when no request is left
+/// behind the head.
+pub struct Scan;
+
+impl DiskScheduler for Scan {
+    fn name(&self) -> &'static str {
+        "Scan"
+    }
+
+    fn schedule(
+        &self,
+        queue: &[Request],
+        head: u32,
+        direction: Direction,
+        geometry: Geometry,
+    ) -> Schedule {
+        let (mut left, right) = split_at_head(queue, head, direction);
+        left.reverse();
+        let mut schedule = Schedule::new(head);
+
+        match direction {
+            Direction::Left => {
+                schedule.extend_requests(left);
+                if right.is_empty() {
+                    return schedule;
+                }
+                if schedule.position() != 0 {
+                    schedule.visit(0, StepKind::EdgeSweep);
+                }
+                schedule.extend_requests(right);
+            }
+
+            Direction::Right => {
+                schedule.extend_requests(right);
+                if left.is_empty() {
+                    return schedule;
+                }
+                if schedule.position() != geometry.max_cylinder {
+                    schedule.visit(geometry.max_cylinder, StepKind::EdgeSweep);
+                }
+                schedule.extend_requests(left);
+            }
+        }
+        schedule
+    }
+}
+
+/// Circular SCAN: sweeps to the edge in `direction`, jumps to the opposite
+/// edge and keeps sweeping the same way. When no request is left behind the
+/// head it stops at the last request instead.
+pub struct CScan;
+
+impl DiskScheduler for CScan {
+    fn name(&self) -> &'static str {
+        "Circular Scan"
+    }
+
+    fn schedule(
+        &self,
+        queue: &[Request],
+        head: u32,
+        direction: Direction,
+        geometry: Geometry,
+    ) -> Schedule {
+        let (mut left, mut right) = split_at_head(queue, head, direction);
+        let mut schedule = Schedule::new(head);
+
+        match direction {
+            Direction::Left => {
+                left.reverse();
+                right.reverse();
+
+                schedule.extend_requests(left);
+                if right.is_empty() {
+                    return schedule;
+                }
+                if schedule.position() != 0 {
+                    schedule.visit(0, StepKind::EdgeSweep);
+                }
+                if right[0] == geometry.max_cylinder {
+                    schedule.jump_back(right);
+                } else {
+                    schedule.visit(
+                        geometry.max_cylinder,
+                        StepKind::ReturnJump {
+                            lands_on_request: false,
+                        },
+                    );
+                    schedule.extend_requests(right);
+                }
+            }
+
+            Direction::Right => {
+                schedule.extend_requests(right);
+                if left.is_empty() {
+                    return schedule;
+                }
+                if schedule.position() != geometry.max_cylinder {
+                    schedule.visit(geometry.max_cylinder, StepKind::EdgeSweep);
+                }
+                if left[0] == 0 {
+                    schedule.jump_back(left);
+                } else {
+                    schedule.visit(
+                        0,
+                        StepKind::ReturnJump {
+                            lands_on_request: false,
+                        },
+                    );
+                    schedule.extend_requests(left);
+                }
+            }
+        }
+        schedule
+    }
+}
+
+/// LOOK: like SCAN, but the head turns around at the last request instead of
+/// the disk edge.
+pub struct Look;
+
+impl DiskScheduler for Look {
+    fn name(&self) -> &'static str {
+        "Look"
+    }
+
+    fn schedule(
+        &self,
+        queue: &[Request],
+        head: u32,
+        direction: Direction,
+        _geometry: Geometry,
+    ) -> Schedule {
+        let (mut left, mut right) = split_at_head(queue, head, direction);
+        left.reverse();
+        let mut order: Vec<u32> = Vec::new();
+
+        match direction {
+            Direction::Left => {
+                order.append(&mut left);
+                order.append(&mut right);
+            }
+
+            Direction::Right => {
+                order.append(&mut right);
+                order.append(&mut left);
+            }
+        }
+        Schedule::from_order(head, order)
+    }
+}
+
+/// Circular LOOK: like C-SCAN, but jumps between the outermost requests
+/// instead of the disk edges.
+pub struct CLook;
+
+impl DiskScheduler for CLook {
+    fn name(&self) -> &'static str {
+        "Circular Look"
+    }
+
+    fn schedule(
+        &self,
+        queue: &[Request],
+        head: u32,
+        direction: Direction,
+        _geometry: Geometry,
+    ) -> Schedule {
+        let (mut left, mut right) = split_at_head(queue, head, direction);
+        let mut schedule = Schedule::new(head);
+
+        match direction {
+            Direction::Left => {
+                left.reverse();
+                right.reverse();
+                schedule.extend_requests(left);
+                schedule.jump_back(right);
+            }
+
+            Direction::Right => {
+                schedule.extend_requests(right);
+                schedule.jump_back(left);
+            }
+        }
+        schedule
+    }
+}
+
+/// Runs SCAN over each of `batches` in turn, carrying the head position and
+/// direction of travel from one batch to the next.
+fn scan_batches<'a>(
+    batches: impl IntoIterator<Item = &'a [Request]>,
+    head: u32,
+    direction: Direction,
+    geometry: Geometry,
+) -> Schedule {
+    let mut schedule = Schedule::new(head);
+    let mut travel = direction;
+    for batch in batches.into_iter().filter(|batch| !batch.is_empty()) {
+        let scan = Scan.schedule(batch, schedule.position(), travel, geometry);
+        if let Some(last) = scan.steps.iter().rev().find(|step| step.distance > 0) {
+            travel = if last.to < last.from {
+                Direction::Left
+            } else {
+                Direction::Right
+            };
+        }
+        schedule.append_batch(scan);
+    }
+    schedule
+}
+
+/// N-Step SCAN: splits the queue into batches of `batch_size` requests in
+/// arrival order and SCANs each batch to completion before looking at the
+/// next, so a stream of new requests near the head cannot hold it in place.
+pub struct NStepScan {
+    pub batch_size: usize,
+}
+
+impl DiskScheduler for NStepScan {
+    fn name(&self) -> &'static str {
+        "N-Step Scan"
+    }
+
+    fn schedule(
+        &self,
+        queue: &[Request],
+        head: u32,
+        direction: Direction,
+        geometry: Geometry,
+    ) -> Schedule {
+        scan_batches(
+            queue.chunks(self.batch_size.max(1)),
+            head,
+            direction,
+            geometry,
+        )
+    }
+
+    fn batching(&self) -> Option<Batching> {
+        Some(Batching::Oldest(self.batch_size.max(1)))
+    }
+}
+
+/// F-SCAN: SCANs the requests that were pending when the sweep started while
+/// newly arriving requests wait in a second queue, which becomes the next batch.
+pub struct FScan;
+
+impl DiskScheduler for FScan {
+    fn name(&self) -> &'static str {
+        "F-Scan"
+    }
+
+    fn schedule(
+        &self,
+        queue: &[Request],
+        head: u32,
+        direction: Direction,
+        geometry: Geometry,
+    ) -> Schedule {
+        // A queue that is full from the start is a single batch.
+        scan_batches([queue], head, direction, geometry)
+    }
+
+    fn batching(&self) -> Option<Batching> {
+        Some(Batching::AllPending)
+    }
+}
+
+/// Shortest Positioning Time First (also Shortest Access Time First):
+/// repeatedly services the pending request the head can reach soonest,
+/// counting both the seek and the wait for its sector to come round.
+///
+/// Without a platter this is SSTF measured in seek time.
+pub struct Sptf {
+    pub seek: SeekModel,
+}
+
+impl DiskScheduler for Sptf {
+    fn name(&self) -> &'static str {
+        "Shortest Positioning Time First"
+    }
+
+    fn schedule(
+        &self,
+        queue: &[Request],
+        head: u32,
+        direction: Direction,
+        geometry: Geometry,
+    ) -> Schedule {
+        self.schedule_from(queue, head, direction, geometry, 0.0)
+    }
+
+    fn schedule_from(
+        &self,
+        queue: &[Request],
+        head: u32,
+        _direction: Direction,
+        geometry: Geometry,
+        start_ms: f64,
+    ) -> Schedule {
+        let mut pending = queue.to_vec();
+        let mut schedule = Schedule::new(head);
+        let mut pos = head;
+        let mut now = start_ms;
+
+        while !pending.is_empty() {
+            let next = pending.remove(self.nearest(&pending, pos, geometry, now));
+            let distance = pos.abs_diff(next.cylinder);
+            now += access(&self.seek, geometry, distance, Some(next.sector), now).total_ms();
+            pos = next.cylinder;
+            schedule.visit(pos, StepKind::Request);
+        }
+        schedule
+    }
+
+    fn next_step(
+        &self,
+        queue: &[Request],
+        head: u32,
+        _direction: Direction,
+        geometry: Geometry,
+        start_ms: f64,
+    ) -> Option<Step> {
+        let next = queue.get(self.nearest(queue, head, geometry, start_ms))?;
+        Some(Step::request(head, next.cylinder))
+    }
+}
+
+impl Sptf {
+    /// Position in `pending` of the request the head can start reading
+    /// soonest from `pos` at `now`. Ties go to the request that arrived first.
+    fn nearest(&self, pending: &[Request], pos: u32, geometry: Geometry, now: f64) -> usize {
+        let positioning = |request: &Request| {
+            let distance = pos.abs_diff(request.cylinder);
+            let access = access(&self.seek, geometry, distance, Some(request.sector), now);
+            access.seek_ms + access.rotation_ms
+        };
+        let mut best = 0;
+        for i in 1..pending.len() {
+            if positioning(&pending[i]) < positioning(&pending[best]) {
+                best = i;
+            }
+        }
+        best
+    }
+}
+
+/// The knobs of Linux's `deadline` and `mq-deadline` schedulers, with the
+/// kernel's defaults.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct DeadlineTunables {
+    /// How long a read may wait before it is serviced out of sorted order.
+    pub read_expire_ms: f64,
+    pub write_expire_ms: f64,
+    /// Requests serviced in sorted order before the FIFOs are checked again.
+    pub fifo_batch: u32,
+    /// Batches of reads that may go ahead of pending writes.
+    pub writes_starved: u32,
+}
+
+impl Default for DeadlineTunables {
+    fn default() -> Self {
+        Self {
+            read_expire_ms: 500.0,
+            write_expire_ms: 5000.0,
+            fifo_batch: 16,
+            writes_starved: 2,
+        }
+    }
+}
+
+impl DeadlineTunables {
+    fn expire_ms(&self, op: Op) -> f64 {
+        match op {
+            Op::Read => self.read_expire_ms,
+            Op::Write => self.write_expire_ms,
+        }
+    }
+}
+
+/// Linux's `deadline` I/O scheduler, whose policy `mq-deadline` keeps.
+///
+/// Reads and writes wait in separate queues, each kept both sorted by
+/// cylinder and in arrival order. The head sweeps upwards through one queue in
+/// batches of up to `fifo_batch` requests, preferring reads unless writes have
+/// been passed over `writes_starved` times. A new batch starts from the oldest
+/// request instead of the next one in sorted order once that request's
+/// deadline has expired.
+pub struct Deadline {
+    pub seek: SeekModel,
+    pub tunables: DeadlineTunables,
+}
+
+impl DiskScheduler for Deadline {
+    fn name(&self) -> &'static str {
+        "Deadline"
+    }
+
+    fn schedule(
+        &self,
+        queue: &[Request],
+        head: u32,
+        direction: Direction,
+        geometry: Geometry,
+    ) -> Schedule {
+        // Without a simulation every request is queued from the start.
+        let queued: Vec<Request> = queue
+            .iter()
+            .map(|request| Request {
+                arrival_ms: 0.0,
+                ..*request
+            })
+            .collect();
+        self.schedule_from(&queued, head, direction, geometry, 0.0)
+    }
+
+    fn schedule_from(
+        &self,
+        queue: &[Request],
+        head: u32,
+        _direction: Direction,
+        geometry: Geometry,
+        start_ms: f64,
+    ) -> Schedule {
+        let tunables = self.tunables;
+        let mut by_arrival: Vec<usize> = (0..queue.len()).collect();
+        by_arrival.sort_by(|&a, &b| queue[a].arrival_ms.total_cmp(&queue[b].arrival_ms));
+        let mut next_arrival = 0;
+        let mut pending: Vec<usize> = Vec::new();
+
+        let mut schedule = Schedule::new(head);
+        let mut now = start_ms;
+        let mut batching = 0;
+        let mut starved = 0;
+        // The queue the last request came from; the other one has no next request.
+        let mut last_op: Option<Op> = None;
+
+        loop {
+            while next_arrival < by_arrival.len()
+                && queue[by_arrival[next_arrival]].arrival_ms <= now
+            {
+                pending.push(by_arrival[next_arrival]);
+                next_arrival += 1;
+            }
+            if pending.is_empty() {
+                match by_arrival.get(next_arrival) {
+                    Some(&next) => {
+                        now = queue[next].arrival_ms;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+
+            let pos = schedule.position();
+            // The request after the head in sorted order, in the queue of `op`.
+            let next_sorted = |op: Op| {
+                pending
+                    .iter()
+                    .copied()
+                    .filter(|&i| queue[i].op == op && queue[i].cylinder >= pos)
+                    .min_by_key(|&i| queue[i].cylinder)
+                    .filter(|_| last_op == Some(op))
+            };
+            // `pending` is in arrival order, so the first match is the FIFO head.
+            let oldest = |op: Op| pending.iter().copied().find(|&i| queue[i].op == op);
+
+            let continued = last_op
+                .filter(|_| batching < tunables.fifo_batch)
+                .and_then(next_sorted);
+            let (chosen, kind) = match continued {
+                Some(next) => (next, StepKind::Request),
+                None => {
+                    let has_reads = oldest(Op::Read).is_some();
+                    let has_writes = oldest(Op::Write).is_some();
+                    let op = if has_reads && !(has_writes && starved >= tunables.writes_starved) {
+                        if has_writes {
+                            starved += 1;
+                        }
+                        Op::Read
+                    } else {
+                        starved = 0;
+                        Op::Write
+                    };
+
+                    let Some(fifo) = oldest(op) else { break };
+                    let expired = queue[fifo].arrival_ms + tunables.expire_ms(op) <= now;
+                    batching = 0;
+                    schedule.batch_starts.push(schedule.steps.len());
+                    match next_sorted(op) {
+                        Some(next) if !expired => (next, StepKind::Request),
+                        Some(next) if next != fifo => (fifo, StepKind::DeadlineExpiry),
+                        _ => (fifo, StepKind::Request),
+                    }
+                }
+            };
+
+            let request = queue[chosen];
+            let distance = pos.abs_diff(request.cylinder);
+            now += access(&self.seek, geometry, distance, Some(request.sector), now).total_ms();
+            schedule.visit(request.cylinder, kind);
+            pending.retain(|&i| i != chosen);
+            batching += 1;
+            last_op = Some(request.op);
+        }
+        schedule
+    }
+
+    fn plans_arrivals(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISK: Geometry = Geometry {
+        max_cylinder: 199,
+        platter: None,
+    };
+
+    fn run(
+        scheduler: &dyn DiskScheduler,
+        cylinders: &[u32],
+        head: u32,
+        dir: Direction,
+    ) -> Schedule {
+        scheduler.schedule(&Request::queue(cylinders), head, dir, DISK)
+    }
+
+    /// The request queue of the worked examples in Silberschatz et al.,
+    /// Operating System Concepts, with the head at 53 on cylinders 0 to 199.
+    const TEXTBOOK: [u32; 8] = [98, 183, 37, 122, 14, 124, 65, 67];
+
+    fn textbook(algorithm: Algorithm, dir: Direction) -> Schedule {
+        let tuning = Tuning::default();
+        run(algorithm.scheduler(&tuning).as_ref(), &TEXTBOOK, 53, dir)
+    }
+
+    /// The cylinders of the steps that service a request.
+    fn serviced(schedule: &Schedule) -> Vec<u32> {
+        schedule
+            .steps
+            .iter()
+            .filter(|step| step.services_request())
+            .map(|step| step.to)
+            .collect()
+    }
+
+    #[test]
+    fn textbook_fcfs_sstf_and_sptf() {
+        let fcfs = textbook(Algorithm::Fcfs, Direction::Right);
+        assert_eq!(fcfs.order, TEXTBOOK);
+        assert_eq!(fcfs.total_movement(true), 640);
+
+        let sstf = textbook(Algorithm::Sstf, Direction::Right);
+        assert_eq!(sstf.order, vec![65, 67, 37, 14, 98, 122, 124, 183]);
+        assert_eq!(sstf.total_movement(true), 236);
+
+        // Without a platter SPTF is SSTF measured in seek time.
+        assert_eq!(textbook(Algorithm::Sptf, Direction::Right), sstf);
+    }
+
+    #[test]
+    fn textbook_scan_and_look() {
+        let scan = textbook(Algorithm::Scan, Direction::Left);
+        assert_eq!(scan.order, vec![37, 14, 0, 65, 67, 98, 122, 124, 183]);
+        assert_eq!(scan.total_movement(true), 236);
+        assert_eq!(scan.steps[2].kind, StepKind::EdgeSweep);
+
+        let scan = textbook(Algorithm::Scan, Direction::Right);
+        assert_eq!(scan.order, vec![65, 67, 98, 122, 124, 183, 199, 37, 14]);
+        assert_eq!(scan.total_movement(true), 331);
+
+        let look = textbook(Algorithm::Look, Direction::Right);
+        assert_eq!(look.order, vec![65, 67, 98, 122, 124, 183, 37, 14]);
+        assert_eq!(look.total_movement(true), 299);
+
+        let look = textbook(Algorithm::Look, Direction::Left);
+        assert_eq!(look.total_movement(true), 208);
+    }
+
+    #[test]
+    fn textbook_cscan_and_clook() {
+        let cscan = textbook(Algorithm::CScan, Direction::Right);
+        assert_eq!(cscan.order, vec![65, 67, 98, 122, 124, 183, 199, 0, 14, 37]);
+        assert_eq!(
+            cscan.steps[7].kind,
+            StepKind::ReturnJump {
+                lands_on_request: false
+            }
+        );
+        assert_eq!(cscan.total_movement(true), 382);
+        assert_eq!(cscan.total_movement(false), 183);
+
+        let cscan = textbook(Algorithm::CScan, Direction::Left);
+        assert_eq!(cscan.order, vec![37, 14, 0, 199, 183, 124, 122, 98, 67, 65]);
+        assert_eq!(cscan.total_movement(true), 386);
+
+        let clook = textbook(Algorithm::CLook, Direction::Right);
+        assert_eq!(clook.order, vec![65, 67, 98, 122, 124, 183, 14, 37]);
+        assert_eq!(
+            clook.steps[6].kind,
+            StepKind::ReturnJump {
+                lands_on_request: true
+            }
+        );
+        assert_eq!(clook.total_movement(true), 322);
+        assert_eq!(clook.total_movement(false), 153);
+    }
+
+    #[test]
+    fn return_jump_onto_a_request_at_the_edge() {
+        let cscan = run(&CScan, &[0, 60, 199], 53, Direction::Right);
+        assert_eq!(cscan.order, vec![60, 199, 0]);
+        assert!(cscan.steps.iter().all(Step::services_request));
+        assert_eq!(cscan.total_movement(false), 146);
+    }
+
+    #[test]
+    fn scan_and_cscan_stop_when_nothing_is_behind_the_head() {
+        for scheduler in [&Scan as &dyn DiskScheduler, &CScan] {
+            let schedule = run(scheduler, &[60, 70], 53, Direction::Right);
+            assert_eq!(schedule.order, vec![60, 70]);
+            assert_eq!(schedule.total_movement(true), 17);
+            assert!(schedule.steps.iter().all(Step::services_request));
+
+            let schedule = run(scheduler, &[40, 30], 53, Direction::Left);
+            assert_eq!(schedule.order, vec![40, 30]);
+            assert_eq!(schedule.total_movement(true), 23);
+        }
+    }
+
+    #[test]
+    fn scan_and_cscan_stay_put_on_an_empty_queue() {
+        for dir in [Direction::Left, Direction::Right] {
+            assert_eq!(run(&Scan, &[], 53, dir), Schedule::new(53));
+            assert_eq!(run(&CScan, &[], 53, dir), Schedule::new(53));
+        }
+    }
+
+    #[test]
+    fn requests_under_the_head_are_serviced_first() {
+        let queue = [50, 40, 60];
+        let schedule = run(&CScan, &queue, 50, Direction::Left);
+        assert_eq!(schedule.order, vec![50, 40, 0, 199, 60]);
+        assert_eq!(schedule.total_movement(true), 388);
+
+        let schedule = run(&CLook, &queue, 50, Direction::Left);
+        assert_eq!(schedule.order, vec![50, 40, 60]);
+        assert_eq!(schedule.total_movement(true), 30);
+
+        let schedule = run(&Scan, &queue, 50, Direction::Left);
+        assert_eq!(schedule.order, vec![50, 40, 0, 60]);
+        assert_eq!(schedule.total_movement(true), 110);
+
+        let schedule = run(&Look, &queue, 50, Direction::Right);
+        assert_eq!(schedule.order, vec![50, 60, 40]);
+    }
+
+    #[test]
+    fn sstf_tie_breaks() {
+        let sstf =
+            |tie_break, queue: &[u32], dir| serviced(&run(&Sstf { tie_break }, queue, 50, dir));
+        assert_eq!(
+            sstf(TieBreak::LowerCylinder, &[60, 40], Direction::Right),
+            vec![40, 60]
+        );
+        assert_eq!(
+            sstf(TieBreak::DirectionOfTravel, &[40, 60], Direction::Right),
+            vec![60, 40]
+        );
+        assert_eq!(
+            sstf(TieBreak::DirectionOfTravel, &[60, 40], Direction::Left),
+            vec![40, 60]
+        );
+        assert_eq!(
+            sstf(TieBreak::FirstArrived, &[60, 40], Direction::Left),
+            vec![60, 40]
+        );
+    }
+
+    #[test]
+    fn sptf_waits_for_the_platter() {
+        // 10 ms a revolution, 1 ms a sector.
+        let geometry = Geometry {
+            max_cylinder: 199,
+            platter: Some(Platter {
+                rpm: 6000,
+                sectors_per_track: 10,
+            }),
+        };
+        let queue = [Request::new(51), Request::new(60).on_sector(3)];
+        // Reaching cylinder 51 takes 1.1 ms, just too late for sector 0, while
+        // sector 3 of cylinder 60 comes round 1 ms after its 2 ms seek.
+        let sptf = Sptf {
+            seek: SeekModel::linear(),
+        };
+        let schedule = sptf.schedule(&queue, 50, Direction::Left, geometry);
+        assert_eq!(schedule.order, vec![60, 51]);
+    }
+
+    #[test]
+    fn batching_schedulers() {
+        let nstep = NStepScan { batch_size: 4 };
+        let schedule = run(&nstep, &TEXTBOOK, 53, Direction::Right);
+        // SCAN over 98, 183, 37, 122, then from 37, still heading left, over
+        // 14, 124, 65, 67.
+        assert_eq!(
+            schedule.order,
+            vec![98, 122, 183, 199, 37, 14, 0, 65, 67, 124]
+        );
+        assert_eq!(schedule.batch_starts, vec![0, 5]);
+        assert_eq!(schedule.total_movement(true), 469);
+
+        let fscan = run(&FScan, &TEXTBOOK, 53, Direction::Right);
+        let scan = run(&Scan, &TEXTBOOK, 53, Direction::Right);
+        assert_eq!(fscan.order, scan.order);
+        assert_eq!(fscan.batch_starts, vec![0]);
+    }
+
+    fn deadline(tunables: DeadlineTunables) -> Deadline {
+        Deadline {
+            seek: SeekModel::linear(),
+            tunables,
+        }
+    }
+
+    #[test]
+    fn deadline_sweeps_up_in_batches() {
+        let schedule = textbook(Algorithm::Deadline, Direction::Right);
+        // Nothing expires, so the head sweeps up from the oldest request and
+        // starts again from the oldest one left whenever it runs out.
+        assert_eq!(schedule.order, vec![98, 122, 124, 183, 37, 65, 67, 14]);
+        assert_eq!(schedule.total_movement(true), 359);
+    }
+
+    #[test]
+    fn deadline_services_expired_requests_first() {
+        let scheduler = deadline(DeadlineTunables {
+            read_expire_ms: 5.0,
+            fifo_batch: 1,
+            ..Default::default()
+        });
+        let schedule = run(&scheduler, &[98, 183, 37, 122], 53, Direction::Right);
+        // The seek to 98 takes 5.5 ms, by which time 183 has expired.
+        assert_eq!(schedule.order, vec![98, 183, 37, 122]);
+        let kinds: Vec<StepKind> = schedule.steps.iter().map(|step| step.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                StepKind::Request,
+                StepKind::DeadlineExpiry,
+                StepKind::Request,
+                StepKind::Request
+            ]
+        );
+    }
+
+    #[test]
+    fn deadline_lets_writes_in_after_starving() {
+        let queue = [
+            Request::new(60).with_op(Op::Write),
+            Request::new(70),
+            Request::new(80),
+        ];
+        let order = |writes_starved| {
+            let scheduler = deadline(DeadlineTunables {
+                fifo_batch: 1,
+                writes_starved,
+                ..Default::default()
+            });
+            scheduler.schedule(&queue, 50, Direction::Right, DISK).order
+        };
+        assert_eq!(order(1), vec![70, 60, 80]);
+        assert_eq!(order(2), vec![70, 80, 60]);
+    }
+
+    #[test]
+    fn parses_cylinders() {
+        assert_eq!(
+            parse_cylinders("98, 183;37\n 122"),
+            Ok(vec![98, 183, 37, 122])
+        );
+        assert_eq!(parse_cylinders(" "), Ok(vec![]));
+        assert!(parse_cylinders("98, x").is_err());
+        assert!(parse_cylinders("-1").is_err());
+    }
+
+    #[test]
+    fn parses_and_formats_requests() {
+        let requests = parse_requests("98, w183:7@12.5; 37\n").unwrap();
+        assert_eq!(
+            requests,
+            vec![
+                Request::new(98),
+                Request::arriving_at(183, 12.5)
+                    .on_sector(7)
+                    .with_op(Op::Write),
+                Request::new(37),
+            ]
+        );
+        assert_eq!(format_requests(&requests, ","), "98:0@0,w183:7@12.5,37:0@0");
+        assert_eq!(
+            parse_requests(&format_requests(&requests, " ")),
+            Ok(requests)
+        );
+        assert_eq!(format_requests(&Request::queue(&[1, 2]), ", "), "1, 2");
+
+        assert!(parse_requests("98, x").is_err());
+        assert!(parse_requests("98@-1").is_err());
+        assert!(parse_requests("98:x").is_err());
+    }
+
+    #[test]
+    fn parses_names() {
+        for algorithm in Algorithm::ALL {
+            let key = format!("{algorithm:?}");
+            assert_eq!(key.parse(), Ok(algorithm));
+        }
+        assert_eq!("c-look".parse(), Ok(Algorithm::CLook));
+        assert_eq!("mq-deadline".parse(), Ok(Algorithm::Deadline));
+        assert!("elevator".parse::<Algorithm>().is_err());
+        assert_eq!("L".parse(), Ok(Direction::Left));
+        assert_eq!("first-arrived".parse(), Ok(TieBreak::FirstArrived));
+    }
+}
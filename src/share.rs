@@ -0,0 +1,183 @@
+//! Links that open the app on a given workload, for posting exercises.
+//!
+//! The workload travels in the URL hash (or query) as `key=value` pairs, e.g.
+//! `#c=200&h=53&d=left&r=98,183,w37:12@4&a=sstf`, the requests written the
+//! way the sequence text field reads them.
+
+use crate::scenario::key;
+use crate::sched::{
+    format_requests, parse_requests, Algorithm, Direction, Request, MAX_CYLINDERS, MAX_REQUESTS,
+};
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Link {
+    pub cylinders: u32,
+    pub head: u32,
+    pub direction: Direction,
+    pub requests: Vec<Request>,
+    /// The algorithm to open, or the comparison of all of them when `None`.
+    pub algorithm: Option<Algorithm>,
+}
+
+impl Link {
+    /// The link as URL hash or query text, without the leading `#` or `?`.
+    pub fn to_fragment(&self) -> String {
+        let mut fragment = format!(
+            "c={}&h={}&d={}&r={}",
+            self.cylinders,
+            self.head,
+            key(self.direction),
+            format_requests(&self.requests, ",")
+        );
+        if let Some(algorithm) = self.algorithm {
+            fragment.push_str(&format!("&a={}", key(algorithm)));
+        }
+        fragment
+    }
+
+    /// Reads a link from URL hash or query text, with or without its leading
+    /// `#` or `?`. Returns `None` when the text holds no link at all.
+    pub fn parse(text: &str) -> Option<Result<Self, String>> {
+        let text = text.trim_start_matches(['#', '?']);
+        if text.is_empty() {
+            return None;
+        }
+        let field = |name: &str| {
+            text.split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|&(key, _)| key == name)
+                .map(|(_, value)| value)
+        };
+        // Pages may carry hashes of their own; only those with requests are links.
+        let requests = field("r")?;
+        Some(Self::parse_fields(field, requests))
+    }
+
+    fn parse_fields<'a>(
+        field: impl Fn(&str) -> Option<&'a str>,
+        requests: &str,
+    ) -> Result<Self, String> {
+        let number = |name: &str, what: &str| -> Result<Option<u32>, String> {
+            field(name)
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid {what} `{value}` in link"))
+                })
+                .transpose()
+        };
+        let requests = parse_requests(requests)?;
+        if requests.len() > MAX_REQUESTS {
+            return Err(format!(
+                "link has {} requests, more than the {MAX_REQUESTS} allowed",
+                requests.len()
+            ));
+        }
+        let cylinders = match number("c", "cylinder count")? {
+            Some(cylinders) => cylinders.max(1),
+            None => match requests.iter().map(|request| request.cylinder).max() {
+                Some(last) => last
+                    .checked_add(1)
+                    .ok_or_else(|| format!("cylinder {last} is too large"))?,
+                None => 1,
+            },
+        };
+        if cylinders > MAX_CYLINDERS {
+            return Err(format!(
+                "{cylinders} cylinders is more than the {MAX_CYLINDERS} allowed"
+            ));
+        }
+        if let Some(request) = requests
+            .iter()
+            .find(|request| request.cylinder >= cylinders)
+        {
+            return Err(format!(
+                "cylinder {} is outside 0..{cylinders}",
+                request.cylinder
+            ));
+        }
+        let head = number("h", "head")?.unwrap_or(0);
+        if head >= cylinders {
+            return Err(format!("head {head} is outside 0..{cylinders}"));
+        }
+        Ok(Self {
+            cylinders,
+            head,
+            direction: field("d")
+                .map(str::parse)
+                .transpose()?
+                .unwrap_or(Direction::Left),
+            requests,
+            algorithm: field("a").map(str::parse).transpose()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sched::Op;
+
+    #[test]
+    fn round_trips() {
+        let link = Link {
+            cylinders: 200,
+            head: 53,
+            direction: Direction::Right,
+            requests: vec![
+                Request::new(98),
+                Request::arriving_at(183, 4.0)
+                    .on_sector(12)
+                    .with_op(Op::Write),
+            ],
+            algorithm: Some(Algorithm::CLook),
+        };
+        let fragment = link.to_fragment();
+        assert_eq!(fragment, "c=200&h=53&d=right&r=98:0@0,w183:12@4&a=c-look");
+        assert_eq!(Link::parse(&format!("#{fragment}")), Some(Ok(link.clone())));
+        assert_eq!(Link::parse(&format!("?{fragment}")), Some(Ok(link)));
+    }
+
+    #[test]
+    fn fills_in_what_is_left_out() {
+        let link = Link::parse("r=98,183,37").unwrap().unwrap();
+        assert_eq!(link.cylinders, 184);
+        assert_eq!(link.head, 0);
+        assert_eq!(link.direction, Direction::Left);
+        assert_eq!(link.requests, Request::queue(&[98, 183, 37]));
+        assert_eq!(link.algorithm, None);
+    }
+
+    #[test]
+    fn ignores_hashes_that_are_not_links() {
+        assert_eq!(Link::parse(""), None);
+        assert_eq!(Link::parse("#"), None);
+        assert_eq!(Link::parse("#section-2"), None);
+        assert_eq!(Link::parse("#c=200&h=53"), None);
+    }
+
+    #[test]
+    fn rejects_bad_links() {
+        let error = |text| Link::parse(text).unwrap().unwrap_err();
+        assert_eq!(error("c=100&r=98,183"), "cylinder 183 is outside 0..100");
+        assert_eq!(error("c=200&h=200&r=98"), "head 200 is outside 0..200");
+        assert_eq!(error("c=big&r=98"), "invalid cylinder count `big` in link");
+        assert_eq!(error("r=4294967295"), "cylinder 4294967295 is too large");
+        assert_eq!(
+            error("r=1000000"),
+            "1000001 cylinders is more than the 1000000 allowed"
+        );
+        assert_eq!(
+            error("c=4000000000&r=98"),
+            "4000000000 cylinders is more than the 1000000 allowed"
+        );
+        let requests = vec!["1"; MAX_REQUESTS + 1].join(",");
+        assert_eq!(
+            error(&format!("r={requests}")),
+            "link has 10001 requests, more than the 10000 allowed"
+        );
+        assert!(Link::parse("r=98&d=sideways").unwrap().is_err());
+        assert!(Link::parse("r=98&a=elevator").unwrap().is_err());
+        assert!(Link::parse("r=98,x").unwrap().is_err());
+    }
+}
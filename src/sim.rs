@@ -0,0 +1,605 @@
+//! Discrete-event simulation of a disk whose requests arrive over time.
+//!
+//! The scheduler is handed the requests that have arrived but not been
+//! serviced yet, and the head follows the plan it returns. Whenever new
+//! requests join the queue, the scheduler plans again from where the head is;
+//! schedulers that pick one request at a time are asked for every move.
+//! Requests arriving while the head is moving are only seen once it stops.
+//!
+//! When the geometry has a platter, every serviced request also waits for its
+//! sector to come round under the head and for it to be read.
+
+use crate::sched::{
+    list_tokens, Batching, Direction, DiskScheduler, Geometry, Request, Schedule, Step,
+};
+
+/// Turns head movement into time.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Debug, Clone)]
+pub enum SeekModel {
+    /// Every cylinder costs the same, plus a fixed cost for any seek that moves
+    /// the head (start-up and settle).
+    Linear {
+        overhead_ms: f64,
+        per_cylinder_ms: f64,
+    },
+    /// Short seeks are dominated by the arm accelerating and braking, so their
+    /// time grows with the square root of the distance. Past `coast_from`
+    /// cylinders the arm reaches full speed and the time grows linearly.
+    SqrtLinear {
+        settle_ms: f64,
+        /// Milliseconds per square root of a cylinder.
+        sqrt_ms: f64,
+        coast_from: u32,
+        per_cylinder_ms: f64,
+    },
+    /// Measured `(distance, ms)` points sorted by distance, interpolated
+    /// linearly and extrapolated from the last two points.
+    Table(Vec<(u32, f64)>),
+}
+
+impl Default for SeekModel {
+    fn default() -> Self {
+        Self::linear()
+    }
+}
+
+impl SeekModel {
+    pub fn linear() -> Self {
+        Self::Linear {
+            overhead_ms: 1.0,
+            per_cylinder_ms: 0.1,
+        }
+    }
+
+    /// Roughly the curve of a small hard drive.
+    pub fn sqrt_linear() -> Self {
+        Self::SqrtLinear {
+            settle_ms: 1.0,
+            sqrt_ms: 0.4,
+            coast_from: 200,
+            per_cylinder_ms: 0.015,
+        }
+    }
+
+    pub fn table() -> Self {
+        Self::Table(vec![(1, 1.0), (10, 2.2), (100, 5.0), (1000, 12.0)])
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SeekModel::Linear { .. } => "Linear",
+            SeekModel::SqrtLinear { .. } => "Square Root + Linear",
+            SeekModel::Table(_) => "Lookup Table",
+        }
+    }
+
+    pub fn seek_ms(&self, distance: u32) -> f64 {
+        if distance == 0 {
+            return 0.0;
+        }
+        let d = distance as f64;
+        match self {
+            SeekModel::Linear {
+                overhead_ms,
+                per_cylinder_ms,
+            } => overhead_ms + per_cylinder_ms * d,
+            SeekModel::SqrtLinear {
+                settle_ms,
+                sqrt_ms,
+                coast_from,
+                per_cylinder_ms,
+            } => {
+                let accelerating = d.min(*coast_from as f64);
+                let coasting = d - accelerating;
+                settle_ms + sqrt_ms * accelerating.sqrt() + per_cylinder_ms * coasting
+            }
+            SeekModel::Table(points) => interpolate(points, d),
+        }
+    }
+
+    /// Estimated time the head spends seeking over `schedule`, leaving out the
+    /// return jump unless `count_return_jump` is set.
+    pub fn total_ms(&self, schedule: &Schedule, count_return_jump: bool) -> f64 {
+        schedule
+            .steps
+            .iter()
+            .map(|step| self.seek_ms(step.counted_distance(count_return_jump)))
+            .sum()
+    }
+}
+
+fn interpolate(points: &[(u32, f64)], d: f64) -> f64 {
+    match points {
+        [] => return 0.0,
+        [(_, ms)] => return *ms,
+        [(first, ms), ..] if d <= *first as f64 => return *ms,
+        _ => {}
+    }
+    // Past the last point, keep going along the last segment.
+    let segment = points
+        .windows(2)
+        .find(|pair| d <= pair[1].0 as f64)
+        .unwrap_or(&points[points.len() - 2..]);
+    let ((d0, ms0), (d1, ms1)) = (segment[0], segment[1]);
+    if d1 == d0 {
+        return ms1;
+    }
+    (ms0 + (ms1 - ms0) * (d - d0 as f64) / (d1 - d0) as f64).max(0.0)
+}
+
+/// Parses a seek curve written as `distance:ms` pairs separated by commas,
+/// semicolons and/or whitespace.
+pub fn parse_seek_table(text: &str) -> Result<Vec<(u32, f64)>, String> {
+    let points = list_tokens(text)
+        .map(|token| {
+            let (distance, ms) = token
+                .split_once(':')
+                .ok_or_else(|| format!("`{token}` is not a distance:ms pair"))?;
+            let distance = distance
+                .parse()
+                .map_err(|_| format!("`{token}` has an invalid distance"))?;
+            let ms = ms
+                .parse()
+                .ok()
+                .filter(|ms: &f64| ms.is_finite() && *ms >= 0.0)
+                .ok_or_else(|| format!("`{token}` has an invalid seek time"))?;
+            Ok((distance, ms))
+        })
+        .collect::<Result<Vec<(u32, f64)>, String>>()?;
+    seek_table(points)
+}
+
+/// Sorts the `(distance, ms)` points of a seek curve by distance and checks
+/// that they describe one.
+pub fn seek_table(mut points: Vec<(u32, f64)>) -> Result<Vec<(u32, f64)>, String> {
+    if let Some((distance, _)) = points.iter().find(|(_, ms)| !ms.is_finite() || *ms < 0.0) {
+        return Err(format!("distance {distance} has an invalid seek time"));
+    }
+    points.sort_by_key(|&(distance, _)| distance);
+    if let Some(pair) = points.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+        return Err(format!("distance {} is listed twice", pair[0].0));
+    }
+    // A longer seek never takes less time.
+    if let Some(pair) = points.windows(2).find(|pair| pair[1].1 < pair[0].1) {
+        return Err(format!(
+            "seeking {} cylinders takes less time than seeking {}",
+            pair[1].0, pair[0].0
+        ));
+    }
+    Ok(points)
+}
+
+/// Where the time of one visit of the head goes.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct Access {
+    pub seek_ms: f64,
+    /// Waiting for the requested sector to rotate under the head.
+    pub rotation_ms: f64,
+    pub transfer_ms: f64,
+}
+
+impl Access {
+    pub fn total_ms(&self) -> f64 {
+        self.seek_ms + self.rotation_ms + self.transfer_ms
+    }
+}
+
+/// Times a seek over `distance` cylinders starting at `start_ms`, followed by
+/// reading one `sector` when one is given and the platter is modelled.
+pub fn access(
+    seek: &SeekModel,
+    geometry: Geometry,
+    distance: u32,
+    sector: Option<u32>,
+    start_ms: f64,
+) -> Access {
+    let seek_ms = seek.seek_ms(distance);
+    match (geometry.platter, sector) {
+        (Some(platter), Some(sector)) => Access {
+            seek_ms,
+            rotation_ms: platter.latency_ms(sector, start_ms + seek_ms),
+            transfer_ms: platter.sector_ms(),
+        },
+        _ => Access {
+            seek_ms,
+            ..Default::default()
+        },
+    }
+}
+
+/// Of the requests in `candidates` sitting on `cylinder`, the position of the
+/// one whose sector comes round first for a head that is in place at
+/// `time_ms`. Ties, and disks without a platter, keep the order of `candidates`.
+fn pick_on_cylinder(
+    queue: &[Request],
+    candidates: &[usize],
+    cylinder: u32,
+    geometry: Geometry,
+    time_ms: f64,
+) -> Option<usize> {
+    let latency = |i: usize| {
+        geometry
+            .platter
+            .map_or(0.0, |platter| platter.latency_ms(queue[i].sector, time_ms))
+    };
+    let mut best: Option<usize> = None;
+    for (at, &i) in candidates.iter().enumerate() {
+        if queue[i].cylinder != cylinder {
+            continue;
+        }
+        if best.map_or(true, |best| latency(i) < latency(candidates[best])) {
+            best = Some(at);
+        }
+    }
+    best
+}
+
+/// A head movement together with when it happened.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct TimedStep {
+    pub step: Step,
+    /// The sector read at the end of the step, if it serviced a request.
+    pub sector: Option<u32>,
+    pub access: Access,
+    pub start_ms: f64,
+    pub finish_ms: f64,
+}
+
+/// Times a schedule computed for a queue that was full from the start.
+pub fn time_schedule(
+    schedule: &Schedule,
+    queue: &[Request],
+    geometry: Geometry,
+    seek: &SeekModel,
+) -> Vec<TimedStep> {
+    let mut pending: Vec<usize> = (0..queue.len()).collect();
+    let mut now = 0.0;
+    schedule
+        .steps
+        .iter()
+        .map(|&step| {
+            let sector = if step.services_request() {
+                let arrive_ms = now + seek.seek_ms(step.distance);
+                pick_on_cylinder(queue, &pending, step.to, geometry, arrive_ms)
+                    .map(|at| queue[pending.remove(at)].sector)
+            } else {
+                None
+            };
+            let access = access(seek, geometry, step.distance, sector, now);
+            let start_ms = now;
+            now += access.total_ms();
+            TimedStep {
+                step,
+                sector,
+                access,
+                start_ms,
+                finish_ms: now,
+            }
+        })
+        .collect()
+}
+
+/// When a single request was serviced.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Service {
+    /// Index of the request in the simulated queue.
+    pub request: usize,
+    pub cylinder: u32,
+    pub arrival_ms: f64,
+    /// When the head started moving towards the request.
+    pub start_ms: f64,
+    pub finish_ms: f64,
+}
+
+impl Service {
+    /// Time spent in the queue before the head started moving towards the request.
+    pub fn waiting_ms(&self) -> f64 {
+        self.start_ms - self.arrival_ms
+    }
+
+    /// Time from arrival until the request was serviced.
+    pub fn response_ms(&self) -> f64 {
+        self.finish_ms - self.arrival_ms
+    }
+}
+
+/// The requests waiting in the queue from `time_ms` until the next snapshot.
+#[derive(PartialEq, Debug, Clone)]
+pub struct QueueSnapshot {
+    pub time_ms: f64,
+    /// Indices into the simulated queue, in arrival order.
+    pub pending: Vec<usize>,
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Simulation {
+    /// The moves the head made, in the same form the static schedulers return.
+    pub schedule: Schedule,
+    /// `schedule.steps` with their timing.
+    pub steps: Vec<TimedStep>,
+    /// One entry per request, in the order they were serviced.
+    pub services: Vec<Service>,
+    pub timeline: Vec<QueueSnapshot>,
+}
+
+impl Simulation {
+    pub fn run(
+        scheduler: &dyn DiskScheduler,
+        queue: &[Request],
+        head: u32,
+        direction: Direction,
+        geometry: Geometry,
+        seek: &SeekModel,
+    ) -> Self {
+        let mut by_arrival: Vec<usize> = (0..queue.len()).collect();
+        by_arrival.sort_by(|&a, &b| queue[a].arrival_ms.total_cmp(&queue[b].arrival_ms));
+
+        let mut sim = Simulation {
+            schedule: Schedule::new(head),
+            ..Default::default()
+        };
+        let mut next_arrival = 0;
+        let mut pending: Vec<usize> = Vec::new();
+        // What is left of the current batch, for schedulers that work in batches.
+        let mut batch: Vec<usize> = Vec::new();
+        let mut now = 0.0;
+        let mut travel = direction;
+        // Schedulers that plan around arrivals themselves are asked only once.
+        let fixed_plan = scheduler
+            .plans_arrivals()
+            .then(|| scheduler.schedule_from(queue, head, direction, geometry, 0.0));
+        // The moves planned for the current candidates and how many are done.
+        let mut plan: Vec<Step> = Vec::new();
+        let mut planned = 0;
+
+        loop {
+            let mut arrived = false;
+            while next_arrival < by_arrival.len()
+                && queue[by_arrival[next_arrival]].arrival_ms <= now
+            {
+                pending.push(by_arrival[next_arrival]);
+                next_arrival += 1;
+                arrived = true;
+            }
+            if arrived {
+                sim.snapshot(now, &pending);
+            }
+
+            if pending.is_empty() {
+                match by_arrival.get(next_arrival) {
+                    // The head idles until the next request shows up.
+                    Some(&next) => {
+                        now = queue[next].arrival_ms;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+
+            // A batch only takes in new requests once it is done.
+            let mut replan = arrived && scheduler.batching().is_none();
+            if let Some(batching) = scheduler.batching() {
+                if batch.is_empty() {
+                    let size = match batching {
+                        Batching::Oldest(size) => size,
+                        Batching::AllPending => pending.len(),
+                    };
+                    batch = pending.iter().copied().take(size).collect();
+                    sim.schedule.batch_starts.push(sim.steps.len());
+                    replan = true;
+                }
+            }
+            let candidates = match scheduler.batching() {
+                Some(_) => &batch,
+                None => &pending,
+            };
+
+            let step = if let Some(fixed_plan) = &fixed_plan {
+                let Some(&step) = fixed_plan.steps.get(sim.steps.len()) else {
+                    break;
+                };
+                step
+            } else {
+                if replan || planned == plan.len() {
+                    let waiting: Vec<Request> = candidates.iter().map(|&i| queue[i]).collect();
+                    let pos = sim.schedule.position();
+                    plan = match scheduler.next_step(&waiting, pos, travel, geometry, now) {
+                        Some(step) => vec![step],
+                        None => {
+                            scheduler
+                                .schedule_from(&waiting, pos, travel, geometry, now)
+                                .steps
+                        }
+                    };
+                    planned = 0;
+                }
+                // Skip zero-length edge visits: the head is already sitting on that edge.
+                while plan
+                    .get(planned)
+                    .is_some_and(|step| step.distance == 0 && !step.services_request())
+                {
+                    planned += 1;
+                }
+                let Some(&step) = plan.get(planned) else {
+                    break;
+                };
+                planned += 1;
+                step
+            };
+
+            let start_ms = now;
+            let serviced = if step.services_request() {
+                let arrive_ms = now + seek.seek_ms(step.distance);
+                pick_on_cylinder(queue, candidates, step.to, geometry, arrive_ms)
+                    .map(|at| candidates[at])
+            } else {
+                None
+            };
+            if let Some(request) = serviced {
+                pending.retain(|&i| i != request);
+                batch.retain(|&i| i != request);
+            }
+            let sector = serviced.map(|request| queue[request].sector);
+            let access = access(seek, geometry, step.distance, sector, now);
+            now += access.total_ms();
+            sim.schedule.visit(step.to, step.kind);
+            sim.steps.push(TimedStep {
+                step,
+                sector,
+                access,
+                start_ms,
+                finish_ms: now,
+            });
+
+            if let Some(request) = serviced {
+                sim.services.push(Service {
+                    request,
+                    cylinder: step.to,
+                    arrival_ms: queue[request].arrival_ms,
+                    start_ms,
+                    finish_ms: now,
+                });
+                sim.snapshot(now, &pending);
+            }
+
+            // C-SCAN and C-LOOK keep sweeping the same way after jumping back.
+            if !step.is_return_jump() {
+                match step.to.cmp(&step.from) {
+                    std::cmp::Ordering::Less => travel = Direction::Left,
+                    std::cmp::Ordering::Greater => travel = Direction::Right,
+                    std::cmp::Ordering::Equal => {}
+                }
+            }
+        }
+        if let Some(plan) = fixed_plan {
+            sim.schedule.batch_starts = plan.batch_starts;
+        }
+        sim
+    }
+
+    fn snapshot(&mut self, time_ms: f64, pending: &[usize]) {
+        // Several events at the same instant collapse into one snapshot.
+        if let Some(last) = self.timeline.last_mut() {
+            if last.time_ms == time_ms {
+                last.pending = pending.to_vec();
+                return;
+            }
+        }
+        self.timeline.push(QueueSnapshot {
+            time_ms,
+            pending: pending.to_vec(),
+        });
+    }
+
+    /// When the last request was serviced.
+    pub fn makespan_ms(&self) -> f64 {
+        self.steps.last().map_or(0.0, |step| step.finish_ms)
+    }
+
+    pub fn mean_response_ms(&self) -> f64 {
+        if self.services.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = self.services.iter().map(Service::response_ms).sum();
+        total / self.services.len() as f64
+    }
+
+    pub fn max_response_ms(&self) -> f64 {
+        self.services
+            .iter()
+            .map(Service::response_ms)
+            .fold(0.0, f64::max)
+    }
+
+    pub fn mean_waiting_ms(&self) -> f64 {
+        if self.services.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = self.services.iter().map(Service::waiting_ms).sum();
+        total / self.services.len() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sched::{Algorithm, Fcfs, Tuning};
+
+    const DISK: Geometry = Geometry {
+        max_cylinder: 199,
+        platter: None,
+    };
+
+    #[test]
+    fn seek_tables_must_not_get_faster_with_distance() {
+        assert_eq!(
+            parse_seek_table("10:2.2, 1:1 100:5"),
+            Ok(vec![(1, 1.0), (10, 2.2), (100, 5.0)])
+        );
+        assert!(parse_seek_table("1:1, 10:3, 100:2").is_err());
+        assert!(parse_seek_table("1:1, 1:2").is_err());
+        assert!(parse_seek_table("1:-1").is_err());
+    }
+
+    #[test]
+    fn interpolated_seek_times_are_never_negative() {
+        let table = SeekModel::Table(vec![(1, 1.0), (10, 2.2), (100, 5.0)]);
+        assert_eq!(table.seek_ms(0), 0.0);
+        assert!((table.seek_ms(55) - 3.6).abs() < 1e-9);
+        assert!((table.seek_ms(190) - 7.8).abs() < 1e-9);
+
+        // Built directly, a table can still fall; its extrapolation stops at 0.
+        let falling = SeekModel::Table(vec![(1, 5.0), (10, 1.0)]);
+        assert_eq!(falling.seek_ms(100), 0.0);
+    }
+
+    #[test]
+    fn fcfs_keeps_arrival_order_under_the_head() {
+        let queue = Request::queue(&[10, 50]);
+        let sim = Simulation::run(
+            &Fcfs,
+            &queue,
+            50,
+            Direction::Left,
+            DISK,
+            &SeekModel::linear(),
+        );
+        assert_eq!(sim.schedule.order, vec![10, 50]);
+        let serviced: Vec<usize> = sim.services.iter().map(|service| service.request).collect();
+        assert_eq!(serviced, vec![0, 1]);
+    }
+
+    #[test]
+    fn a_full_queue_simulates_like_the_static_schedule() {
+        let queues = [
+            Request::queue(&[98, 183, 37, 122, 14, 124, 65, 67, 53]),
+            // Nothing behind the head going right.
+            Request::queue(&[60, 70]),
+        ];
+        let tuning = Tuning::default();
+        for queue in &queues {
+            for algorithm in Algorithm::ALL {
+                let scheduler = algorithm.scheduler(&tuning);
+                for direction in [Direction::Left, Direction::Right] {
+                    let expected = scheduler.schedule(queue, 53, direction, DISK);
+                    let sim = Simulation::run(
+                        scheduler.as_ref(),
+                        queue,
+                        53,
+                        direction,
+                        DISK,
+                        &tuning.seek,
+                    );
+                    let context = format!("{algorithm:?} {direction:?} {queue:?}");
+                    assert_eq!(sim.schedule.order, expected.order, "{context}");
+                    assert_eq!(
+                        sim.schedule.total_movement(true),
+                        expected.total_movement(true),
+                        "{context}"
+                    );
+                    assert_eq!(sim.services.len(), queue.len());
+                }
+            }
+        }
+    }
+}
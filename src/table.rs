@@ -0,0 +1,334 @@
+//! Schedules and their statistics as tables for lab reports, written as CSV,
+//! GitHub-flavoured Markdown or a LaTeX `tabular`.
+
+use crate::metrics::{self, WaitStats};
+use crate::sched::Schedule;
+use crate::sim::SeekModel;
+
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum TableFormat {
+    Csv,
+    Markdown,
+    Latex,
+}
+
+impl TableFormat {
+    pub const ALL: [TableFormat; 3] = [TableFormat::Csv, TableFormat::Markdown, TableFormat::Latex];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TableFormat::Csv => "CSV",
+            TableFormat::Markdown => "Markdown",
+            TableFormat::Latex => "LaTeX",
+        }
+    }
+
+    /// File extension, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            TableFormat::Csv => "csv",
+            TableFormat::Markdown => "md",
+            TableFormat::Latex => "tex",
+        }
+    }
+}
+
+impl std::str::FromStr for TableFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(TableFormat::Csv),
+            "markdown" | "md" => Ok(TableFormat::Markdown),
+            "latex" | "tex" => Ok(TableFormat::Latex),
+            _ => Err(format!(
+                "unknown table format `{s}` (expected `csv`, `markdown` or `latex`)"
+            )),
+        }
+    }
+}
+
+/// Cells as they are printed, one `Vec` per row.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Table {
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn render(&self, format: TableFormat) -> String {
+        match format {
+            TableFormat::Csv => self.to_csv(),
+            TableFormat::Markdown => self.to_markdown(),
+            TableFormat::Latex => self.to_latex(),
+        }
+    }
+
+    fn lines(&self) -> impl Iterator<Item = &Vec<String>> {
+        std::iter::once(&self.header).chain(&self.rows)
+    }
+
+    /// Columns whose cells are all numbers are right-aligned.
+    fn numeric(&self, column: usize) -> bool {
+        !self.rows.is_empty()
+            && self.rows.iter().all(|row| {
+                row.get(column)
+                    .is_some_and(|cell| cell.parse::<f64>().is_ok())
+            })
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        for line in self.lines() {
+            let cells: Vec<String> = line
+                .iter()
+                .map(|cell| {
+                    if cell.contains([',', '"', '\n']) {
+                        format!("\"{}\"", cell.replace('"', "\"\""))
+                    } else {
+                        cell.clone()
+                    }
+                })
+                .collect();
+            csv.push_str(&cells.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    fn to_markdown(&self) -> String {
+        let row = |cells: &[String]| {
+            let cells: Vec<String> = cells.iter().map(|cell| cell.replace('|', "\\|")).collect();
+            format!("| {} |\n", cells.join(" | "))
+        };
+        let rule: Vec<String> = (0..self.header.len())
+            .map(|column| {
+                if self.numeric(column) {
+                    "---:".to_owned()
+                } else {
+                    "---".to_owned()
+                }
+            })
+            .collect();
+
+        let mut markdown = row(&self.header);
+        markdown.push_str(&format!("|{}|\n", rule.join("|")));
+        for cells in &self.rows {
+            markdown.push_str(&row(cells));
+        }
+        markdown
+    }
+
+    fn to_latex(&self) -> String {
+        let columns: String = (0..self.header.len())
+            .map(|column| if self.numeric(column) { 'r' } else { 'l' })
+            .collect();
+        let row = |cells: &[String]| {
+            let cells: Vec<String> = cells.iter().map(|cell| escape_latex(cell)).collect();
+            format!("{} \\\\\n", cells.join(" & "))
+        };
+
+        let mut latex = format!("\\begin{{tabular}}{{{columns}}}\n\\hline\n");
+        latex.push_str(&row(&self.header));
+        latex.push_str("\\hline\n");
+        for cells in &self.rows {
+            latex.push_str(&row(cells));
+        }
+        latex.push_str("\\hline\n\\end{tabular}\n");
+        latex
+    }
+}
+
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Every step of `schedule` with its distance, the running total and the
+/// estimated seek time.
+pub fn step_table(schedule: &Schedule, seek: &SeekModel, count_return_jump: bool) -> Table {
+    let header = [
+        "Step",
+        "From",
+        "To",
+        "Distance",
+        "Cumulative",
+        "Seek (ms)",
+        "Kind",
+    ];
+    let mut cumulative = 0;
+    let rows = schedule
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let distance = step.counted_distance(count_return_jump);
+            cumulative += distance;
+            vec![
+                (i + 1).to_string(),
+                step.from.to_string(),
+                step.to.to_string(),
+                step.distance_label(count_return_jump),
+                cumulative.to_string(),
+                format!("{:.2}", seek.seek_ms(distance)),
+                step.kind.step_label().to_owned(),
+            ]
+        })
+        .collect();
+    Table {
+        header: header.map(str::to_owned).to_vec(),
+        rows,
+    }
+}
+
+/// One row per `(name, schedule)` pair: the service order, head movement,
+/// seek time, direction reversals and how many steps requests waited.
+/// `queue` holds the requested cylinders in arrival order.
+pub fn summary_table(
+    schedules: &[(&str, &Schedule)],
+    queue: &[u32],
+    seek: &SeekModel,
+    count_return_jump: bool,
+) -> Table {
+    let header = [
+        "Algorithm",
+        "Service Order",
+        "Total Seek",
+        "Average Seek",
+        "Seek Time (ms)",
+        "Reversals",
+        "Mean Wait (steps)",
+        "Max Wait (steps)",
+    ];
+    let rows = schedules
+        .iter()
+        .map(|&(name, schedule)| {
+            let order: Vec<String> = schedule
+                .steps
+                .iter()
+                .filter(|step| step.services_request())
+                .map(|step| step.to.to_string())
+                .collect();
+            let total = schedule.total_movement(count_return_jump);
+            let waits: Vec<f64> = metrics::step_waits(schedule, queue)
+                .into_iter()
+                .flatten()
+                .collect();
+            let waits = WaitStats::from_waits(&waits);
+            vec![
+                name.to_owned(),
+                order.join(", "),
+                total.to_string(),
+                format!("{:.2}", total as f64 / queue.len().max(1) as f64),
+                format!("{:.2}", seek.total_ms(schedule, count_return_jump)),
+                schedule.direction_reversals().to_string(),
+                format!("{:.2}", waits.mean),
+                format!("{:.0}", waits.max),
+            ]
+        })
+        .collect();
+    Table {
+        header: header.map(str::to_owned).to_vec(),
+        rows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sched::{CLook, Direction, DiskScheduler, Geometry, Request};
+
+    fn table() -> Table {
+        Table {
+            header: vec!["Name".to_owned(), "Total".to_owned()],
+            rows: vec![
+                vec!["C-LOOK, \"fast\"".to_owned(), "12".to_owned()],
+                vec!["a|b_&%".to_owned(), "3.5".to_owned()],
+            ],
+        }
+    }
+
+    #[test]
+    fn renders_csv() {
+        assert_eq!(
+            table().render(TableFormat::Csv),
+            "Name,Total\n\"C-LOOK, \"\"fast\"\"\",12\na|b_&%,3.5\n"
+        );
+    }
+
+    #[test]
+    fn renders_markdown() {
+        assert_eq!(
+            table().render(TableFormat::Markdown),
+            "| Name | Total |\n|---|---:|\n| C-LOOK, \"fast\" | 12 |\n| a\\|b_&% | 3.5 |\n"
+        );
+    }
+
+    #[test]
+    fn renders_latex() {
+        assert_eq!(
+            table().render(TableFormat::Latex),
+            "\\begin{tabular}{lr}\n\\hline\nName & Total \\\\\n\\hline\n\
+             C-LOOK, \"fast\" & 12 \\\\\na|b\\_\\&\\% & 3.5 \\\\\n\\hline\n\\end{tabular}\n"
+        );
+        assert_eq!(
+            escape_latex("~^\\$#{}"),
+            "\\textasciitilde{}\\textasciicircum{}\\textbackslash{}\\$\\#\\{\\}"
+        );
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!("MD".parse(), Ok(TableFormat::Markdown));
+        assert_eq!("tex".parse(), Ok(TableFormat::Latex));
+        assert!("html".parse::<TableFormat>().is_err());
+    }
+
+    #[test]
+    fn step_and_summary_tables() {
+        let queue = [98, 183, 37];
+        let schedule = CLook.schedule(
+            &Request::queue(&queue),
+            53,
+            Direction::Right,
+            Geometry::cylinders(199),
+        );
+        let seek = SeekModel::linear();
+        let steps = step_table(&schedule, &seek, false);
+        let column = |column: usize| -> Vec<&str> {
+            steps.rows.iter().map(|row| row[column].as_str()).collect()
+        };
+        assert_eq!(column(2), vec!["98", "183", "37"]);
+        // The jump back to 37 lands on a request and is left out of the total,
+        // and the table says both, as the step table on screen does.
+        assert_eq!(column(3), vec!["45", "85", "146 (not counted)"]);
+        assert_eq!(column(4), vec!["45", "130", "130"]);
+        assert_eq!(steps.rows[2][5], "0.00");
+        assert_eq!(
+            column(6),
+            vec!["Request", "Request", "Return Jump + Request"]
+        );
+        let counted = step_table(&schedule, &seek, true);
+        assert_eq!(counted.rows[2][3], "146");
+        assert_eq!(counted.rows[2][4], "276");
+
+        let summary = summary_table(&[("C-LOOK", &schedule)], &queue, &seek, true);
+        assert_eq!(summary.rows[0][1], "98, 183, 37");
+        assert_eq!(summary.rows[0][2], "276");
+        assert_eq!(summary.rows[0][3], "92.00");
+        assert_eq!(summary.rows[0][6], "1.00");
+        assert_eq!(summary.rows[0][7], "2");
+    }
+}
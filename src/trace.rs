@@ -0,0 +1,456 @@
+//! Turning recorded block I/O traces into request queues.
+
+use crate::sched::{Op, Request};
+
+/// Which blktrace event marks a request's arrival.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum TraceEvent {
+    /// `Q`: the request was queued by the block layer.
+    Queue,
+    /// `D`: the request was issued to the driver.
+    Issue,
+}
+
+impl TraceEvent {
+    fn action(self) -> &'static str {
+        match self {
+            TraceEvent::Queue => "Q",
+            TraceEvent::Issue => "D",
+        }
+    }
+}
+
+/// How trace sectors are laid out over the simulated disk.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct SectorMapping {
+    pub max_cylinder: u32,
+    /// Sectors on every cylinder; the sector within the track is what is left over.
+    pub sectors_per_track: u32,
+    /// Stretch the sectors seen in the trace over the whole disk instead of
+    /// dropping the ones that lie past its last cylinder.
+    pub scale_to_fit: bool,
+}
+
+impl SectorMapping {
+    /// Maps absolute `(sector, request)` pairs onto the disk, in place of
+    /// their `cylinder` and `sector`. Requests that do not fit are dropped.
+    fn apply(&self, raw: Vec<(u64, Request)>) -> Vec<Request> {
+        let per_track = self.sectors_per_track.max(1) as u64;
+        let cylinders = self.max_cylinder as u64 + 1;
+        let highest = raw.iter().map(|&(sector, _)| sector).max().unwrap_or(0);
+        // Scaling works on whole tracks so the sector within a track is kept.
+        let tracks = highest / per_track + 1;
+        raw.into_iter()
+            .filter_map(|(sector, request)| {
+                let track = sector / per_track;
+                let cylinder = if self.scale_to_fit {
+                    track * cylinders / tracks
+                } else {
+                    track
+                };
+                (cylinder < cylinders).then_some(Request {
+                    cylinder: cylinder as u32,
+                    sector: (sector % per_track) as u32,
+                    ..request
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parses `blkparse` default text output, or the `time start end` block dumps
+/// `btt -B` writes, keeping one request per `event`.
+///
+/// Arrival times are in milliseconds from the first kept event.
+pub fn parse_blkparse(
+    text: &str,
+    event: TraceEvent,
+    mapping: SectorMapping,
+) -> Result<Vec<Request>, String> {
+    let mut raw: Vec<(f64, u64, Op)> = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let parsed = match fields.as_slice() {
+            // btt -B: time, first sector, last sector.
+            [time, start, end] if time.parse::<f64>().is_ok() && end.parse::<u64>().is_ok() => {
+                Some((*time, *start, Op::Read))
+            }
+            // blkparse: dev cpu seq time pid action rwbs sector + size [process]
+            [_, _, _, time, _, action, rwbs, sector, "+", ..] if *action == event.action() => {
+                let op = if rwbs.contains('W') {
+                    Op::Write
+                } else {
+                    Op::Read
+                };
+                Some((*time, *sector, op))
+            }
+            _ => None,
+        };
+        let Some((time, sector, op)) = parsed else {
+            continue;
+        };
+        let line = number + 1;
+        let time: f64 = time
+            .parse()
+            .ok()
+            .filter(|time: &f64| time.is_finite())
+            .ok_or_else(|| format!("line {line}: invalid timestamp `{time}`"))?;
+        let sector = sector
+            .parse()
+            .map_err(|_| format!("line {line}: invalid sector `{sector}`"))?;
+        raw.push((time, sector, op));
+    }
+
+    if raw.is_empty() {
+        return Err(format!(
+            "no `{}` events with a sector found",
+            event.action()
+        ));
+    }
+    let raw = raw
+        .into_iter()
+        .map(|(time, sector, op)| (sector, Request::arriving_at(0, time * 1000.0).with_op(op)))
+        .collect();
+    Ok(place(raw, mapping))
+}
+
+/// Maps `raw` onto the disk and makes time start at the first request that
+/// made it onto the disk.
+fn place(raw: Vec<(u64, Request)>, mapping: SectorMapping) -> Vec<Request> {
+    let mut requests = mapping.apply(raw);
+    let start = requests
+        .iter()
+        .map(|request| request.arrival_ms)
+        .fold(f64::INFINITY, f64::min);
+    for request in &mut requests {
+        request.arrival_ms -= start;
+    }
+    requests
+}
+
+/// The unit of the timestamp column of a CSV trace.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum TimeUnit {
+    /// Windows filetime, 100 ns ticks, as in the MSR Cambridge traces.
+    FileTime,
+    Seconds,
+    Milliseconds,
+    Microseconds,
+}
+
+impl TimeUnit {
+    pub const ALL: [TimeUnit; 4] = [
+        TimeUnit::FileTime,
+        TimeUnit::Seconds,
+        TimeUnit::Milliseconds,
+        TimeUnit::Microseconds,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TimeUnit::FileTime => "Filetime (100 ns)",
+            TimeUnit::Seconds => "Seconds",
+            TimeUnit::Milliseconds => "Milliseconds",
+            TimeUnit::Microseconds => "Microseconds",
+        }
+    }
+
+    fn to_ms(self, time: f64) -> f64 {
+        match self {
+            TimeUnit::FileTime => time / 10_000.0,
+            TimeUnit::Seconds => time * 1000.0,
+            TimeUnit::Milliseconds => time,
+            TimeUnit::Microseconds => time / 1000.0,
+        }
+    }
+}
+
+/// How to read a CSV block trace. Columns count from 0.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Debug, Clone, Copy)]
+pub struct CsvImport {
+    pub timestamp_column: usize,
+    pub disk_column: usize,
+    /// Holds `Read`/`Write` or `R`/`W`.
+    pub type_column: usize,
+    /// Byte offset of the request on the disk.
+    pub offset_column: usize,
+    pub time_unit: TimeUnit,
+    pub sector_bytes: u32,
+    /// Only keep requests to this disk number.
+    pub disk: Option<u32>,
+    /// Only keep requests in `[from, to]` ms after the first one kept.
+    pub window_ms: Option<[f64; 2]>,
+}
+
+impl Default for CsvImport {
+    /// The MSR Cambridge / SNIA layout:
+    /// `Timestamp,Hostname,DiskNumber,Type,Offset,Size,ResponseTime`.
+    fn default() -> Self {
+        Self {
+            timestamp_column: 0,
+            disk_column: 2,
+            type_column: 3,
+            offset_column: 4,
+            time_unit: TimeUnit::FileTime,
+            sector_bytes: 512,
+            disk: None,
+            window_ms: None,
+        }
+    }
+}
+
+/// Parses a CSV block trace laid out as `import` describes. A first line
+/// without a numeric timestamp is taken as a header.
+pub fn parse_csv(
+    text: &str,
+    import: &CsvImport,
+    mapping: SectorMapping,
+) -> Result<Vec<Request>, String> {
+    let mut raw: Vec<(u64, Request)> = Vec::new();
+    let mut first_ms = None;
+    for (number, line) in text.lines().enumerate() {
+        let line_no = number + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let field = |column: usize| {
+            fields
+                .get(column)
+                .copied()
+                .ok_or_else(|| format!("line {line_no}: no column {column}"))
+        };
+
+        let timestamp = field(import.timestamp_column)?;
+        let Ok(time) = timestamp.parse::<f64>() else {
+            if number == 0 {
+                continue;
+            }
+            return Err(format!("line {line_no}: invalid timestamp `{timestamp}`"));
+        };
+        if let Some(disk) = import.disk {
+            let number = field(import.disk_column)?;
+            if number.parse::<u32>().ok() != Some(disk) {
+                continue;
+            }
+        }
+        let time_ms = import.time_unit.to_ms(time);
+        let since_first = time_ms - *first_ms.get_or_insert(time_ms);
+        if let Some([from, to]) = import.window_ms {
+            if since_first < from || since_first > to {
+                continue;
+            }
+        }
+
+        let op = if field(import.type_column)?
+            .to_ascii_lowercase()
+            .starts_with('w')
+        {
+            Op::Write
+        } else {
+            Op::Read
+        };
+        let offset = field(import.offset_column)?;
+        let offset: u64 = offset
+            .parse()
+            .map_err(|_| format!("line {line_no}: invalid offset `{offset}`"))?;
+        let sector = offset / import.sector_bytes.max(1) as u64;
+        raw.push((sector, Request::arriving_at(0, time_ms).with_op(op)));
+    }
+
+    if raw.is_empty() {
+        return Err("no requests found".to_owned());
+    }
+    Ok(place(raw, mapping))
+}
+
+/// Thins `requests` out evenly to at most `max` of them, keeping their order.
+pub fn sample(requests: Vec<Request>, max: usize) -> Vec<Request> {
+    if requests.len() <= max {
+        return requests;
+    }
+    let max = max.max(1);
+    let stride = (requests.len() + max - 1) / max;
+    requests.into_iter().step_by(stride).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAPPING: SectorMapping = SectorMapping {
+        max_cylinder: 199,
+        sectors_per_track: 100,
+        scale_to_fit: false,
+    };
+
+    /// Cylinder, sector, arrival and operation of every request.
+    fn summary(requests: &[Request]) -> Vec<(u32, u32, f64, Op)> {
+        requests
+            .iter()
+            .map(|request| {
+                (
+                    request.cylinder,
+                    request.sector,
+                    request.arrival_ms,
+                    request.op,
+                )
+            })
+            .collect()
+    }
+
+    const BLKPARSE: &str = "\
+  8,0    3        1     0.000000000   697  Q   R 5312 + 8 [kworker]
+  8,0    3        2     0.000010000   697  D   R 5312 + 8 [kworker]
+  8,0    3        3     0.002000000   697  Q  WS 1207 + 16 [jbd2/sda1-8]
+  8,0    3        4     0.002500000   697  C   R 5312 + 8 [0]
+  8,0    3        5     0.004500000   697  D  WS 1207 + 16 [jbd2/sda1-8]
+CPU3 (8,0):
+ Reads Queued:           1,        4KiB\n";
+
+    #[test]
+    fn reads_blkparse_output() {
+        let queued = parse_blkparse(BLKPARSE, TraceEvent::Queue, MAPPING).unwrap();
+        assert_eq!(
+            summary(&queued),
+            vec![(53, 12, 0.0, Op::Read), (12, 7, 2.0, Op::Write)]
+        );
+
+        let issued = parse_blkparse(BLKPARSE, TraceEvent::Issue, MAPPING).unwrap();
+        assert_eq!(issued.len(), 2);
+        assert!((issued[1].arrival_ms - 4.49).abs() < 1e-9);
+    }
+
+    #[test]
+    fn reads_btt_block_dumps() {
+        let requests = parse_blkparse("1.5 9800 9807\n1.25 300 307\n", TraceEvent::Queue, MAPPING);
+        assert_eq!(
+            summary(&requests.unwrap()),
+            vec![(98, 0, 250.0, Op::Read), (3, 0, 0.0, Op::Read)]
+        );
+    }
+
+    #[test]
+    fn blkparse_errors() {
+        assert_eq!(
+            parse_blkparse("nothing here\n", TraceEvent::Queue, MAPPING),
+            Err("no `Q` events with a sector found".to_owned())
+        );
+        assert_eq!(
+            parse_blkparse(
+                "8,0 3 1 0.1 697 Q R x + 8 [a]\n",
+                TraceEvent::Queue,
+                MAPPING
+            ),
+            Err("line 1: invalid sector `x`".to_owned())
+        );
+    }
+
+    #[test]
+    fn maps_sectors_onto_the_disk() {
+        let text = "0 0 7\n0 1050 1057\n0 99999 99999\n";
+        // Sector 99999 lies on track 999, past the last cylinder.
+        let cut = parse_blkparse(text, TraceEvent::Queue, MAPPING).unwrap();
+        assert_eq!(
+            summary(&cut),
+            vec![(0, 0, 0.0, Op::Read), (10, 50, 0.0, Op::Read)]
+        );
+
+        let scaled = SectorMapping {
+            scale_to_fit: true,
+            ..MAPPING
+        };
+        // 1000 tracks squeezed onto 200 cylinders.
+        let fit = parse_blkparse(text, TraceEvent::Queue, scaled).unwrap();
+        assert_eq!(
+            summary(&fit),
+            vec![
+                (0, 0, 0.0, Op::Read),
+                (2, 50, 0.0, Op::Read),
+                (199, 99, 0.0, Op::Read)
+            ]
+        );
+    }
+
+    const MSR: &str = "\
+Timestamp,Hostname,DiskNumber,Type,Offset,Size,ResponseTime
+128166372003061629,hm,0,Read,5120,4096,100
+128166372003161629,hm,1,Write,10240,4096,100
+128166372003261629,hm,0,Write,1024000,4096,100
+128166372013061629,hm,0,Read,51200,4096,100
+";
+
+    #[test]
+    fn reads_msr_csv_traces() {
+        let requests = parse_csv(MSR, &CsvImport::default(), MAPPING).unwrap();
+        assert_eq!(
+            summary(&requests),
+            vec![
+                (0, 10, 0.0, Op::Read),
+                (0, 20, 10.0, Op::Write),
+                (20, 0, 20.0, Op::Write),
+                (1, 0, 1000.0, Op::Read)
+            ]
+        );
+
+        let import = CsvImport {
+            disk: Some(0),
+            window_ms: Some([5.0, 500.0]),
+            ..Default::default()
+        };
+        let requests = parse_csv(MSR, &import, MAPPING).unwrap();
+        assert_eq!(summary(&requests), vec![(20, 0, 0.0, Op::Write)]);
+    }
+
+    #[test]
+    fn reads_csv_in_other_layouts() {
+        let import = CsvImport {
+            timestamp_column: 2,
+            type_column: 0,
+            offset_column: 1,
+            time_unit: TimeUnit::Seconds,
+            sector_bytes: 4096,
+            ..Default::default()
+        };
+        let requests = parse_csv("W, 40960, 0.5\nR, 0, 0.25\n", &import, MAPPING).unwrap();
+        assert_eq!(
+            summary(&requests),
+            vec![(0, 10, 250.0, Op::Write), (0, 0, 0.0, Op::Read)]
+        );
+    }
+
+    #[test]
+    fn csv_errors() {
+        let import = CsvImport::default();
+        assert_eq!(
+            parse_csv("1,hm\n", &import, MAPPING),
+            Err("line 1: no column 3".to_owned())
+        );
+        assert_eq!(
+            parse_csv("1,hm,0,Read,5120\nx,hm,0,Read,0\n", &import, MAPPING),
+            Err("line 2: invalid timestamp `x`".to_owned())
+        );
+        assert_eq!(
+            parse_csv("1,hm,0,Read,-5\n", &import, MAPPING),
+            Err("line 1: invalid offset `-5`".to_owned())
+        );
+        assert_eq!(
+            parse_csv("\n", &import, MAPPING),
+            Err("no requests found".to_owned())
+        );
+    }
+
+    #[test]
+    fn samples_evenly() {
+        let requests = Request::queue(&[0, 1, 2, 3, 4, 5, 6]);
+        let cylinders = |max| -> Vec<u32> {
+            sample(requests.clone(), max)
+                .iter()
+                .map(|request| request.cylinder)
+                .collect()
+        };
+        assert_eq!(cylinders(3), vec![0, 3, 6]);
+        assert_eq!(cylinders(7), vec![0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(cylinders(0), vec![0]);
+    }
+}
@@ -0,0 +1,112 @@
+//! Browser-only helpers for the web build.
+
+use std::{cell::RefCell, rc::Rc};
+
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// Values that browser callbacks leave for the app to pick up on its next frame.
+pub(crate) type Inbox<T> = Rc<RefCell<Vec<T>>>;
+
+/// Hands `bytes` to the browser as a download called `file_name`.
+pub(crate) fn download(file_name: &str, mime: &str, bytes: &[u8]) -> Result<(), String> {
+    let js_err = |err: wasm_bindgen::JsValue| format!("download failed: {err:?}");
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let mut options = web_sys::BlobPropertyBag::new();
+    options.type_(mime);
+    let blob =
+        web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options).map_err(js_err)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(js_err)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("download failed: no document")?;
+    let anchor: web_sys::HtmlAnchorElement = document
+        .create_element("a")
+        .map_err(js_err)?
+        .dyn_into()
+        .map_err(|_| "download failed: not an anchor element".to_owned())?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+    web_sys::Url::revoke_object_url(&url).map_err(js_err)
+}
+
+/// Points the hash of the page URL at `fragment` without adding a history entry.
+pub(crate) fn replace_hash(fragment: &str) {
+    let Some(history) = web_sys::window().and_then(|window| window.history().ok()) else {
+        return;
+    };
+    let url = format!("#{fragment}");
+    if let Err(err) = history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url)) {
+        log::warn!("cannot update the page URL: {err:?}");
+    }
+}
+
+/// Leaves the page URL hash in `inbox` and repaints `ctx` whenever the user
+/// changes it, by pasting a link or going back and forward. [`replace_hash`]
+/// does not count as a change.
+pub(crate) fn watch_hash(inbox: Inbox<String>, ctx: egui::Context) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let location = window.location();
+    let on_change = Closure::<dyn Fn()>::new(move || {
+        if let Ok(hash) = location.hash() {
+            inbox.borrow_mut().push(hash);
+            ctx.request_repaint();
+        }
+    });
+    if let Err(err) =
+        window.add_event_listener_with_callback("hashchange", on_change.as_ref().unchecked_ref())
+    {
+        log::warn!("cannot follow changes of the page URL: {err:?}");
+        return;
+    }
+    // The listener stays for as long as the page is open.
+    on_change.forget();
+}
+
+/// The full URL of the page, hash included.
+pub(crate) fn page_url() -> Option<String> {
+    web_sys::window()?.location().href().ok()
+}
+
+/// Lets the user pick a file matching `accept` and, once it is read, leaves
+/// its name and text in `inbox` and repaints `ctx`.
+pub(crate) fn upload(
+    accept: &str,
+    inbox: Inbox<(String, Result<String, String>)>,
+    ctx: egui::Context,
+) -> Result<(), String> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("upload failed: no document")?;
+    let input: web_sys::HtmlInputElement = document
+        .create_element("input")
+        .map_err(|err| format!("upload failed: {err:?}"))?
+        .dyn_into()
+        .map_err(|_| "upload failed: not an input element".to_owned())?;
+    input.set_type("file");
+    input.set_accept(accept);
+
+    let picked = input.clone();
+    let on_change = Closure::once_into_js(move || {
+        let Some(file) = picked.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            let name = file.name();
+            let text = wasm_bindgen_futures::JsFuture::from(file.text())
+                .await
+                .ok()
+                .and_then(|text| text.as_string())
+                .ok_or_else(|| format!("cannot read {name}"));
+            inbox.borrow_mut().push((name, text));
+            ctx.request_repaint();
+        });
+    });
+    input.set_onchange(Some(on_change.unchecked_ref()));
+    input.click();
+    Ok(())
+}
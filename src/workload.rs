@@ -0,0 +1,321 @@
+//! Seeded synthetic workloads, so the same settings give the same requests
+//! on every machine.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::sched::{Geometry, Request, MAX_REQUESTS};
+
+/// How requested cylinders are spread over the disk.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Debug, Clone, Copy)]
+pub enum Distribution {
+    Uniform,
+    /// Normally distributed around a hot spot.
+    Hotspot {
+        center: u32,
+        spread: f64,
+    },
+    /// A few cylinders get most of the requests; the popularity of the k-th
+    /// most popular cylinder falls off as `1 / k^exponent`.
+    Zipf {
+        exponent: f64,
+    },
+    /// Runs of consecutive cylinders starting at random places.
+    SequentialRuns {
+        run_length: u32,
+    },
+    /// Two hot spots, the first getting `first_share` of the requests.
+    Bimodal {
+        first: u32,
+        second: u32,
+        spread: f64,
+        first_share: f64,
+    },
+}
+
+impl Distribution {
+    /// Every kind of distribution with settings that suit a disk of `max_cylinder`.
+    pub fn presets(max_cylinder: u32) -> [Distribution; 5] {
+        [
+            Distribution::Uniform,
+            Distribution::Hotspot {
+                center: max_cylinder / 2,
+                spread: max_cylinder as f64 / 10.0,
+            },
+            Distribution::Zipf { exponent: 1.0 },
+            Distribution::SequentialRuns { run_length: 4 },
+            Distribution::Bimodal {
+                first: max_cylinder / 5,
+                second: max_cylinder - max_cylinder / 5,
+                spread: max_cylinder as f64 / 20.0,
+                first_share: 0.5,
+            },
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Distribution::Uniform => "Uniform",
+            Distribution::Hotspot { .. } => "Hotspot",
+            Distribution::Zipf { .. } => "Zipf",
+            Distribution::SequentialRuns { .. } => "Sequential Runs",
+            Distribution::Bimodal { .. } => "Bimodal",
+        }
+    }
+}
+
+/// Everything that determines a generated workload.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Debug, Clone, Copy)]
+pub struct WorkloadSpec {
+    pub distribution: Distribution,
+    pub count: usize,
+    pub seed: u64,
+    /// Mean requests per millisecond of a Poisson arrival process; `None`
+    /// queues every request at time 0.
+    pub arrival_rate: Option<f64>,
+}
+
+impl Default for WorkloadSpec {
+    fn default() -> Self {
+        Self {
+            distribution: Distribution::Uniform,
+            count: 10,
+            seed: 1,
+            arrival_rate: None,
+        }
+    }
+}
+
+/// Draws the requests `spec` describes for a disk of `geometry`, at most
+/// [`MAX_REQUESTS`] of them. When the platter is modelled every request also
+/// gets a uniformly random sector.
+pub fn generate(spec: &WorkloadSpec, geometry: Geometry) -> Vec<Request> {
+    // Not `StdRng`: rand does not promise that its output stays the same.
+    let mut rng = ChaCha8Rng::seed_from_u64(spec.seed);
+    let max = geometry.max_cylinder;
+    let clamp = |cylinder: f64| cylinder.round().clamp(0.0, max as f64) as u32;
+
+    // Zipf ranks are handed to cylinders by a seeded permutation so the hot
+    // cylinders are not all at one end of the disk.
+    let zipf = match spec.distribution {
+        Distribution::Zipf { exponent } => {
+            let n = max as u64 + 1;
+            let mut stride = rng.gen_range(1..=n);
+            while gcd(stride, n) != 1 {
+                stride = rng.gen_range(1..=n);
+            }
+            let offset = rng.gen_range(0..n);
+            Some((Zipf::new(n, exponent), stride, offset))
+        }
+        _ => None,
+    };
+
+    let count = spec.count.min(MAX_REQUESTS);
+    let mut requests = Vec::with_capacity(count);
+    let mut run_left = 0;
+    let mut cylinder = 0;
+    let mut now = 0.0;
+    for _ in 0..count {
+        cylinder = match spec.distribution {
+            Distribution::Uniform => rng.gen_range(0..=max),
+            Distribution::Hotspot { center, spread } => {
+                clamp(center as f64 + spread * standard_normal(&mut rng))
+            }
+            Distribution::Zipf { .. } => {
+                let (zipf, stride, offset) = zipf.as_ref().expect("zipf sampler");
+                let rank = zipf.sample(&mut rng) - 1;
+                ((rank * stride + offset) % zipf.n) as u32
+            }
+            Distribution::SequentialRuns { run_length } => {
+                if run_left == 0 || cylinder >= max {
+                    run_left = run_length.max(1);
+                    rng.gen_range(0..=max)
+                } else {
+                    cylinder + 1
+                }
+            }
+            Distribution::Bimodal {
+                first,
+                second,
+                spread,
+                first_share,
+            } => {
+                let center = if rng.gen::<f64>() < first_share {
+                    first
+                } else {
+                    second
+                };
+                clamp(center as f64 + spread * standard_normal(&mut rng))
+            }
+        };
+        run_left = run_left.saturating_sub(1);
+
+        if let Some(rate) = spec.arrival_rate.filter(|&rate| rate > 0.0) {
+            // Exponential gaps between arrivals make a Poisson process.
+            now += -(1.0 - rng.gen::<f64>()).ln() / rate;
+        }
+        let sector = geometry.platter.map_or(0, |platter| {
+            rng.gen_range(0..platter.sectors_per_track.max(1))
+        });
+        requests.push(Request::arriving_at(cylinder, now).on_sector(sector));
+    }
+    requests
+}
+
+/// Ranks from 1 to `n` where rank `k` is drawn with weight `1 / k^exponent`.
+/// Sampled by rejection-inversion (Hörmann and Derflinger, 1996), which needs
+/// no table of the `n` weights.
+struct Zipf {
+    n: u64,
+    exponent: f64,
+    /// The area under the hat function the samples are drawn from.
+    area: f64,
+}
+
+impl Zipf {
+    fn new(n: u64, exponent: f64) -> Self {
+        let exponent = exponent.max(0.0);
+        let area = if exponent == 1.0 {
+            1.0 + (n as f64).ln()
+        } else {
+            ((n as f64).powf(1.0 - exponent) - exponent) / (1.0 - exponent)
+        };
+        Self { n, exponent, area }
+    }
+
+    /// Inverse of the integral of the hat function, which is 1 up to `x = 1`
+    /// and `x^-exponent` after it.
+    fn inverse_area(&self, area: f64) -> f64 {
+        if area <= 1.0 {
+            area
+        } else if self.exponent == 1.0 {
+            (area - 1.0).exp()
+        } else {
+            (area * (1.0 - self.exponent) + self.exponent).powf(1.0 / (1.0 - self.exponent))
+        }
+    }
+
+    fn sample(&self, rng: &mut ChaCha8Rng) -> u64 {
+        loop {
+            let x = self.inverse_area(rng.gen::<f64>() * self.area);
+            let rank = (x + 1.0).floor().min(self.n as f64);
+            // The hat lies on or above the weight of `rank` all the way from
+            // `rank - 1` to `rank`, so accepting with this ratio leaves
+            // exactly the weights.
+            let mut ratio = rank.powf(-self.exponent);
+            if x > 1.0 {
+                ratio *= x.powf(self.exponent);
+            }
+            if rng.gen::<f64>() < ratio {
+                return rank as u64;
+            }
+        }
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Box-Muller transform.
+fn standard_normal(rng: &mut ChaCha8Rng) -> f64 {
+    let u1 = 1.0 - rng.gen::<f64>();
+    let u2 = rng.gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sched::Platter;
+
+    fn cylinders(spec: &WorkloadSpec) -> Vec<u32> {
+        generate(spec, Geometry::cylinders(199))
+            .iter()
+            .map(|request| request.cylinder)
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_same_requests_on_every_machine() {
+        let expected: [(&str, [u32; 10]); 5] = [
+            ("Uniform", [136, 29, 190, 154, 85, 125, 147, 57, 128, 61]),
+            ("Hotspot", [128, 84, 109, 105, 101, 118, 57, 113, 111, 118]),
+            ("Zipf", [75, 64, 64, 29, 86, 14, 122, 171, 12, 190]),
+            (
+                "Sequential Runs",
+                [136, 137, 138, 139, 29, 30, 31, 32, 190, 191],
+            ),
+            ("Bimodal", [138, 165, 41, 49, 158, 45, 41, 53, 43, 154]),
+        ];
+        for (distribution, (name, expected)) in Distribution::presets(199).into_iter().zip(expected)
+        {
+            let spec = WorkloadSpec {
+                distribution,
+                seed: 42,
+                ..Default::default()
+            };
+            assert_eq!(distribution.name(), name);
+            assert_eq!(cylinders(&spec), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn zipf_ranks_follow_their_weights() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        for exponent in [0.0, 0.5, 1.0, 2.0] {
+            let zipf = Zipf::new(10, exponent);
+            let weights: Vec<f64> = (1..=10).map(|k| (k as f64).powf(-exponent)).collect();
+            let total: f64 = weights.iter().sum();
+            let mut counts = [0; 10];
+            for _ in 0..100_000 {
+                counts[zipf.sample(&mut rng) as usize - 1] += 1;
+            }
+            for (count, weight) in counts.iter().zip(&weights) {
+                let share = *count as f64 / 100_000.0;
+                assert!((share - weight / total).abs() < 0.01, "exponent {exponent}");
+            }
+        }
+    }
+
+    #[test]
+    fn zipf_works_on_huge_disks() {
+        let spec = WorkloadSpec {
+            distribution: Distribution::Zipf { exponent: 1.2 },
+            count: 100,
+            ..Default::default()
+        };
+        let requests = generate(&spec, Geometry::cylinders(u32::MAX));
+        assert_eq!(requests.len(), 100);
+    }
+
+    #[test]
+    fn arrivals_and_sectors_are_seeded_too() {
+        let spec = WorkloadSpec {
+            count: 4,
+            seed: 7,
+            arrival_rate: Some(0.5),
+            ..Default::default()
+        };
+        let geometry = Geometry {
+            max_cylinder: 199,
+            platter: Some(Platter::default()),
+        };
+        let requests = generate(&spec, geometry);
+        let expected = [
+            (28, 0.40192053387817034, 17),
+            (7, 0.5495684759418039, 38),
+            (162, 1.10379315277261, 5),
+            (51, 1.9951612947006274, 23),
+        ];
+        assert_eq!(requests.len(), expected.len());
+        for (request, (cylinder, arrival_ms, sector)) in requests.iter().zip(expected) {
+            assert_eq!(request.cylinder, cylinder);
+            assert!((request.arrival_ms - arrival_ms).abs() < 1e-9);
+            assert_eq!(request.sector, sector);
+        }
+    }
+}